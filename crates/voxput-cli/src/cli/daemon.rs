@@ -13,6 +13,7 @@ trait VoxputDaemon {
     async fn start_recording(&self) -> zbus::Result<()>;
     async fn stop_recording(&self) -> zbus::Result<()>;
     async fn toggle(&self) -> zbus::Result<()>;
+    async fn cancel(&self) -> zbus::Result<()>;
     async fn get_status(&self) -> zbus::Result<(String, String, String)>;
 }
 
@@ -44,6 +45,9 @@ pub struct StopArgs {}
 #[derive(Debug, clap::Args)]
 pub struct ToggleArgs {}
 
+#[derive(Debug, clap::Args)]
+pub struct CancelArgs {}
+
 #[derive(Debug, clap::Args)]
 pub struct StatusArgs {
     /// Print status as JSON.
@@ -81,6 +85,15 @@ pub async fn run_toggle(_args: &ToggleArgs) -> Result<()> {
     Ok(())
 }
 
+pub async fn run_cancel(_args: &CancelArgs) -> Result<()> {
+    let proxy = connect().await?;
+    proxy.cancel().await.map_err(|e| {
+        VoxputError::Config(format!("cancel failed: {e}"))
+    })?;
+    eprintln!("Cancelled.");
+    Ok(())
+}
+
 pub async fn run_status(args: &StatusArgs) -> Result<()> {
     let proxy = connect().await?;
     let (state, transcript, error) = proxy.get_status().await.map_err(|e| {
@@ -11,7 +11,7 @@ pub struct DevicesArgs {
 }
 
 pub fn run(args: &DevicesArgs) -> Result<()> {
-    let backend = CpalBackend;
+    let backend = CpalBackend::default();
     let devices = backend.list_devices()?;
 
     if args.json {
@@ -19,9 +19,27 @@ pub fn run(args: &DevicesArgs) -> Result<()> {
             .map_err(voxput_core::errors::VoxputError::Json)?;
         println!("{json}");
     } else {
+        let mut host = None;
         for device in &devices {
+            if host != Some(device.host.as_str()) {
+                if host.is_some() {
+                    println!();
+                }
+                println!("{}:", device.host);
+                host = Some(device.host.as_str());
+            }
             let marker = if device.is_default { " (default)" } else { "" };
-            println!("{}{}", device.name, marker);
+            println!("  {}{}", device.name, marker);
+            println!("    id:        {}", device.id);
+            println!("    channels:  {}", device.channels);
+            match &device.default_config {
+                Some(config) => println!("    default:   {config}"),
+                None => println!("    default:   unavailable"),
+            }
+            for (i, config) in device.supported_configs.iter().enumerate() {
+                let label = if i == 0 { "supported:" } else { "" };
+                println!("    {label:<10} {config}");
+            }
         }
     }
 
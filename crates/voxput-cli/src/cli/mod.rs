@@ -1,6 +1,7 @@
 pub mod daemon;
 pub mod devices;
 pub mod record;
+pub mod transcribe;
 
 use clap::{Parser, Subcommand};
 use voxput_core::errors::Result;
@@ -22,6 +23,9 @@ pub enum Commands {
     /// Record audio and transcribe to text (standalone, no daemon required)
     Record(record::RecordArgs),
 
+    /// Transcribe existing audio files (no microphone or daemon required)
+    Transcribe(transcribe::TranscribeArgs),
+
     /// List available audio input devices
     Devices(devices::DevicesArgs),
 
@@ -34,6 +38,9 @@ pub enum Commands {
     /// Toggle recording on the voxputd daemon (start if idle, stop if recording)
     Toggle(daemon::ToggleArgs),
 
+    /// Abandon the voxputd daemon's current recording or transcription
+    Cancel(daemon::CancelArgs),
+
     /// Show the voxputd daemon's current state
     Status(daemon::StatusArgs),
 }
@@ -41,10 +48,12 @@ pub enum Commands {
 pub async fn dispatch(cli: Cli) -> Result<()> {
     match cli.command {
         Commands::Record(args) => record::run(&args).await,
+        Commands::Transcribe(args) => transcribe::run(&args).await,
         Commands::Devices(args) => devices::run(&args),
         Commands::Start(args) => daemon::run_start(&args).await,
         Commands::Stop(args) => daemon::run_stop(&args).await,
         Commands::Toggle(args) => daemon::run_toggle(&args).await,
+        Commands::Cancel(args) => daemon::run_cancel(&args).await,
         Commands::Status(args) => daemon::run_status(&args).await,
     }
 }
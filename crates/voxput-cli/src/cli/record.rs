@@ -1,3 +1,4 @@
+use std::io::IsTerminal;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
@@ -5,16 +6,24 @@ use std::time::Duration;
 use clap::Args;
 use crossterm::event::{self, Event};
 use crossterm::terminal;
+use voxput_core::audio::convert::{ChannelMix, ChannelSelect};
 use voxput_core::audio::cpal_backend::CpalBackend;
-use voxput_core::audio::wav::encode_wav;
+use voxput_core::audio::dsp;
+use voxput_core::audio::meter::AudioLevel;
+use voxput_core::audio::trim::trim_silence;
 use voxput_core::audio::{AudioBackend, MIN_DURATION_SECS};
 use voxput_core::config;
-use voxput_core::errors::Result;
-use voxput_core::output::{self, OutputTarget};
-use voxput_core::provider::groq::GroqProvider;
-use voxput_core::provider::{TranscribeOptions, TranscriptionProvider};
+use voxput_core::errors::{Result, VoxputError};
+use voxput_core::guard::check_transcript;
+use voxput_core::output::subtitle::CueOptions;
+use voxput_core::output::{self, OutputFormat, OutputTarget};
+use voxput_core::pipeline::{transcribe_audio, transcribe_segments};
+use voxput_core::provider::{self, TranscribeOptions, TranscriptionProvider};
 use voxput_core::state::{DictationEvent, DictationStateMachine};
 
+/// Width of the input level bar, in terminal cells.
+const METER_WIDTH: usize = 30;
+
 #[derive(Debug, Args)]
 pub struct RecordArgs {
     /// Max recording duration in seconds (0 = record until any key is pressed)
@@ -25,31 +34,104 @@ pub struct RecordArgs {
     #[arg(long, short, default_value = "stdout")]
     pub output: OutputTarget,
 
-    /// Audio input device name (omit to use system default)
+    /// Audio input device: a name substring or /regex/; repeat to give
+    /// fallbacks in order (overrides config; omit to use the system default)
+    #[arg(long)]
+    pub device: Vec<String>,
+
+    /// Input channel to record, counted from 0, or "mix" to average all
+    /// channels (overrides config)
     #[arg(long)]
-    pub device: Option<String>,
+    pub channel: Option<ChannelSelect>,
 
     /// Language hint (ISO 639-1, e.g. "en")
     #[arg(long)]
     pub language: Option<String>,
 
+    /// Translate speech into English instead of transcribing it
+    #[arg(long)]
+    pub translate: bool,
+
+    /// Stop recording automatically once you stop speaking
+    #[arg(long)]
+    pub auto_stop: bool,
+
+    /// Silence in seconds that ends an auto-stopped recording (overrides config)
+    #[arg(long, value_name = "SECS")]
+    pub silence_secs: Option<f32>,
+
+    /// Transcribe while recording, in segments cut at pauses, printing each as it is ready
+    #[arg(long)]
+    pub incremental: bool,
+
     /// Transcription model (overrides config)
     #[arg(long)]
     pub model: Option<String>,
 
-    /// Print transcript as JSON
-    #[arg(long)]
+    #[command(flatten)]
+    pub format: FormatArgs,
+}
+
+/// Output formatting options shared by `record` and `transcribe`.
+#[derive(Debug, Args)]
+pub struct FormatArgs {
+    /// Output format
+    #[arg(long, short, default_value = "text")]
+    pub format: OutputFormat,
+
+    /// Print transcript as JSON (shorthand for `--format json`)
+    #[arg(long, conflicts_with = "format")]
     pub json: bool,
+
+    /// Maximum characters per subtitle line (srt/vtt)
+    #[arg(long, default_value = "42")]
+    pub max_line_chars: usize,
+
+    /// Maximum subtitle cue duration in seconds (srt/vtt)
+    #[arg(long, default_value = "6")]
+    pub max_cue_secs: f64,
+}
+
+impl FormatArgs {
+    pub fn output_format(&self) -> OutputFormat {
+        if self.json {
+            OutputFormat::Json
+        } else {
+            self.format
+        }
+    }
+
+    pub fn cue_options(&self) -> CueOptions {
+        CueOptions {
+            max_line_chars: self.max_line_chars,
+            max_duration_secs: self.max_cue_secs,
+            ..CueOptions::default()
+        }
+    }
 }
 
 pub async fn run(args: &RecordArgs) -> Result<()> {
     let mut sm = DictationStateMachine::new();
 
-    let config = config::load_config()?;
-    let api_key = config.api_key()?;
-    let model = args.model.clone().or(config.model.clone());
+    let mut config = config::load_config()?;
+    if args.model.is_some() {
+        config.model = args.model.clone();
+    }
+    let provider: Arc<dyn TranscriptionProvider> = provider::create_provider(&config)?.into();
+    let translate = args.translate || config.translate;
+    if translate && !provider.supports_translation() {
+        return Err(VoxputError::TranslationUnsupported {
+            provider: provider.name().to_string(),
+        });
+    }
 
-    // Shared flag: set by keypress listener or by the duration timer.
+    let auto_stop = args.auto_stop || config.auto_stop;
+    let incremental = args.incremental || config.incremental;
+    if let Some(secs) = args.silence_secs {
+        config.vad.trailing_silence_secs = secs.max(0.1);
+    }
+
+    // Shared flag: set by keypress listener, the duration timer or auto-stop.
     let stop = Arc::new(AtomicBool::new(false));
 
     // Spawn keypress listener thread.
@@ -82,14 +164,75 @@ pub async fn run(args: &RecordArgs) -> Result<()> {
 
     // Print recording prompt.
     sm.handle(DictationEvent::StartRecording);
+    let hint = if auto_stop { " or pause speaking" } else { "" };
     if args.duration > 0.0 {
-        eprintln!("Recording… press any key to stop (max {:.0}s)", args.duration);
+        eprintln!("Recording… press any key{hint} to stop (max {:.0}s)", args.duration);
     } else {
-        eprintln!("Recording… press any key to stop");
+        eprintln!("Recording… press any key{hint} to stop");
+    }
+
+    let channel_mix = ChannelMix {
+        channel: args.channel.unwrap_or(config.channel_mix.channel),
+        ..config.channel_mix.clone()
+    };
+    let mut backend = CpalBackend::new(config.sample_rate)
+        .with_source(config.source_backend())
+        .with_channel_mix(channel_mix)
+        .with_auto_stop(auto_stop.then(|| config.vad.clone()));
+    let show_meter = std::io::stderr().is_terminal();
+    if show_meter {
+        backend = backend.with_level_callback(draw_level);
     }
 
-    let backend = CpalBackend;
-    let audio = backend.record(args.duration, Arc::clone(&stop), args.device.as_deref())?;
+    let opts = TranscribeOptions {
+        language: args.language.clone(),
+        verbose: args.format.output_format().needs_timestamps() || config.verbose,
+        ..Default::default()
+    };
+
+    // In incremental mode, segments are filtered and normalized as they are
+    // cut and transcribed in the background while recording goes on.
+    let live = if incremental {
+        let (segment_tx, segment_rx) = tokio::sync::mpsc::unbounded_channel();
+        let dsp_opts = config.dsp.clone();
+        backend = backend.with_segments(config.segments.clone(), move |mut segment| {
+            dsp::process(&mut segment.audio, &dsp_opts);
+            let _ = segment_tx.send(segment);
+        });
+        let provider = Arc::clone(&provider);
+        let (opts, chunking, codec) = (opts.clone(), config.chunking.clone(), config.upload_codec);
+        Some(tokio::spawn(async move {
+            let mut shown = 0;
+            transcribe_segments(&*provider, segment_rx, translate, &opts, &chunking, codec, |t| {
+                show_partial(&t.text, &mut shown, show_meter)
+            })
+            .await
+        }))
+    } else {
+        None
+    };
+
+    // A lost device ends the recording early but keeps what was captured.
+    backend = backend.with_warning_callback(move |warning| {
+        if show_meter {
+            eprint!("\r\x1b[2KWarning: {warning}\r\n");
+        } else {
+            eprintln!("Warning: {warning}");
+        }
+    });
+
+    let devices = if args.device.is_empty() {
+        &config.devices
+    } else {
+        &args.device
+    };
+    // Dropping the backend after recording closes the segment channel.
+    let recorded = backend.record(args.duration, Arc::clone(&stop), devices);
+    drop(backend);
+    if show_meter {
+        eprint!("\r\x1b[2K");
+    }
+    let audio = recorded?;
 
     // Ensure raw mode is restored even if the listener thread is still spinning.
     let _ = terminal::disable_raw_mode();
@@ -98,23 +241,38 @@ pub async fn run(args: &RecordArgs) -> Result<()> {
 
     // Guard: providers reject audio shorter than ~0.01 s.
     if audio.duration_secs() < MIN_DURATION_SECS {
-        return Err(voxput_core::errors::VoxputError::Audio(format!(
+        return Err(VoxputError::Audio(format!(
             "Recording too short ({:.3}s); hold a key longer before releasing",
             audio.duration_secs()
         )));
     }
 
-    let wav_bytes = encode_wav(&audio)?;
-    tracing::debug!(bytes = wav_bytes.len(), "WAV encoded");
+    let mut audio = audio;
+    if live.is_none() {
+        // Drop the key click and trailing silence; refuse to upload silence.
+        if config.trim_silence {
+            audio = trim_silence(&audio, &config.trim)?;
+        }
+        dsp::process(&mut audio, &config.dsp);
+    }
 
     eprintln!("Transcribing…");
-    let provider = GroqProvider::new(api_key, model);
-    let opts = TranscribeOptions {
-        language: args.language.clone(),
-        ..Default::default()
+    let transcribed = match live {
+        // Only the segments still in flight are left to wait for.
+        Some(task) => task
+            .await
+            .map_err(|e| VoxputError::Audio(format!("Transcription task failed: {e}")))
+            .and_then(|result| result),
+        None => transcribe_audio(&*provider, &audio, translate, &opts, &config.chunking, config.upload_codec).await,
     };
-
-    let transcript = match provider.transcribe(&wav_bytes, &opts).await {
+    let result = transcribed.and_then(|t| {
+        if config.hallucination_guard {
+            check_transcript(&audio, t, &config.guard)
+        } else {
+            Ok(t)
+        }
+    });
+    let transcript = match result {
         Ok(t) => {
             sm.handle(DictationEvent::TranscriptionComplete(t.text.clone()));
             t
@@ -126,13 +284,43 @@ pub async fn run(args: &RecordArgs) -> Result<()> {
     };
 
     let sink = output::create_sink(args.output);
-    if args.json {
-        let json = serde_json::to_string_pretty(&transcript)
-            .map_err(voxput_core::errors::VoxputError::Json)?;
-        sink.write(&json)?;
+    sink.write(&output::format_transcript(
+        &transcript,
+        args.format.output_format(),
+        &args.format.cue_options(),
+    )?)?;
+
+    Ok(())
+}
+
+/// Print the part of the running transcript `text` after the first `shown`
+/// bytes on its own stderr line. On a terminal the line replaces the level
+/// bar (which is redrawn below it) and ends with `\r\n`, since raw mode may
+/// still be on.
+fn show_partial(text: &str, shown: &mut usize, terminal: bool) {
+    let new = text.get(*shown..).unwrap_or_default().trim();
+    *shown = text.len();
+    if new.is_empty() {
+        return;
+    }
+    if terminal {
+        eprint!("\r\x1b[2K  » {new}\r\n");
     } else {
-        sink.write(&transcript.text)?;
+        eprintln!("  » {new}");
     }
+}
 
-    Ok(())
+/// Redraw the input level bar on the current stderr line: the RMS level as
+/// a filled bar, the peak as a marker.
+fn draw_level(level: AudioLevel) {
+    let cells = |db: f32| (AudioLevel::fraction(db) * METER_WIDTH as f32).round() as usize;
+    let (rms, peak) = (cells(level.rms_db), cells(level.peak_db));
+    let bar: String = (1..=METER_WIDTH)
+        .map(|i| match i {
+            i if i <= rms => '█',
+            i if i == peak => '▏',
+            _ => ' ',
+        })
+        .collect();
+    eprint!("\r  ▕{bar}▏ {:>4.0} dB", level.rms_db.max(-60.0));
 }
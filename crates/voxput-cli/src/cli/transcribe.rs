@@ -0,0 +1,147 @@
+use std::path::{Path, PathBuf};
+
+use clap::Args;
+use serde::Serialize;
+use voxput_core::audio::decode::decode_file;
+use voxput_core::audio::resample::resample;
+use voxput_core::audio::{AudioData, MIN_DURATION_SECS};
+use voxput_core::config;
+use voxput_core::errors::{Result, VoxputError};
+use voxput_core::guard::check_transcript;
+use voxput_core::output::{self, OutputFormat, OutputTarget};
+use voxput_core::pipeline::transcribe_audio;
+use voxput_core::provider::{self, TranscribeOptions, Transcript};
+
+use super::record::FormatArgs;
+
+#[derive(Debug, Args)]
+pub struct TranscribeArgs {
+    /// Audio files to transcribe (WAV, FLAC, MP3 or Ogg Vorbis)
+    #[arg(required = true, value_name = "FILE")]
+    pub files: Vec<PathBuf>,
+
+    /// Output target
+    #[arg(long, short, default_value = "stdout")]
+    pub output: OutputTarget,
+
+    /// Language hint (ISO 639-1, e.g. "en")
+    #[arg(long)]
+    pub language: Option<String>,
+
+    /// Translate speech into English instead of transcribing it
+    #[arg(long)]
+    pub translate: bool,
+
+    /// Transcription model (overrides config)
+    #[arg(long)]
+    pub model: Option<String>,
+
+    #[command(flatten)]
+    pub format: FormatArgs,
+}
+
+pub async fn run(args: &TranscribeArgs) -> Result<()> {
+    let mut config = config::load_config()?;
+    if args.model.is_some() {
+        config.model = args.model.clone();
+    }
+    let format = args.format.output_format();
+    if matches!(format, OutputFormat::Srt | OutputFormat::Vtt) && args.files.len() > 1 {
+        return Err(VoxputError::Output(
+            "Subtitles are written for one file at a time; run voxput transcribe once per file".into(),
+        ));
+    }
+    let provider = provider::create_provider(&config)?;
+    let translate = args.translate || config.translate;
+    if translate && !provider.supports_translation() {
+        return Err(VoxputError::TranslationUnsupported {
+            provider: provider.name().to_string(),
+        });
+    }
+
+    // Decode everything up front so a bad path fails before any upload.
+    let inputs = args
+        .files
+        .iter()
+        .map(|path| load(path, config.sample_rate).map(|audio| (path, audio)))
+        .collect::<Result<Vec<_>>>()?;
+
+    let opts = TranscribeOptions {
+        language: args.language.clone(),
+        verbose: format.needs_timestamps() || config.verbose,
+        ..Default::default()
+    };
+
+    // Files without speech are left out; if none has any, that is the result.
+    let mut transcripts = Vec::with_capacity(inputs.len());
+    for (path, audio) in inputs {
+        eprintln!("Transcribing {}…", path.display());
+        let transcript = transcribe_audio(
+            &*provider,
+            &audio,
+            translate,
+            &opts,
+            &config.chunking,
+            config.upload_codec,
+        )
+        .await?;
+        if !config.hallucination_guard {
+            transcripts.push((path, transcript));
+            continue;
+        }
+        match check_transcript(&audio, transcript, &config.guard) {
+            Ok(transcript) => transcripts.push((path, transcript)),
+            Err(VoxputError::NoSpeech) => {
+                eprintln!("No speech detected in {}; skipping it", path.display())
+            }
+            Err(e) => return Err(e),
+        }
+    }
+    if transcripts.is_empty() {
+        return Err(VoxputError::NoSpeech);
+    }
+
+    // With several files, each transcript is labelled with its file, since
+    // files without speech are left out. JSON transcripts are emitted as one
+    // array so stdout stays valid JSON.
+    let rendered = match format {
+        _ if args.files.len() == 1 => {
+            output::format_transcript(&transcripts[0].1, format, &args.format.cue_options())?
+        }
+        OutputFormat::Json => {
+            let labelled: Vec<_> = transcripts
+                .iter()
+                .map(|(file, transcript)| FileTranscript { file, transcript })
+                .collect();
+            serde_json::to_string_pretty(&labelled)?
+        }
+        _ => transcripts
+            .iter()
+            .map(|(path, t)| format!("==> {} <==\n{}", path.display(), t.text))
+            .collect::<Vec<_>>()
+            .join("\n\n"),
+    };
+
+    output::create_sink(args.output).write(&rendered)
+}
+
+/// A transcript in the JSON array written for several files.
+#[derive(Serialize)]
+struct FileTranscript<'a> {
+    file: &'a Path,
+    #[serde(flatten)]
+    transcript: &'a Transcript,
+}
+
+/// Decode `path` and resample it to the pipeline's sample rate.
+fn load(path: &Path, sample_rate: u32) -> Result<AudioData> {
+    let audio = resample(&decode_file(path)?, sample_rate);
+    if audio.duration_secs() < MIN_DURATION_SECS {
+        return Err(VoxputError::Audio(format!(
+            "{} is too short ({:.3}s)",
+            path.display(),
+            audio.duration_secs()
+        )));
+    }
+    Ok(audio)
+}
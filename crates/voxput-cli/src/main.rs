@@ -1,6 +1,11 @@
 mod cli;
 
 use clap::Parser;
+use voxput_core::errors::VoxputError;
+
+/// Exit status when a recording held no speech, so scripts can tell an
+/// empty dictation apart from a failure.
+const EXIT_NO_SPEECH: i32 = 3;
 
 #[tokio::main]
 async fn main() -> miette::Result<()> {
@@ -10,7 +15,11 @@ async fn main() -> miette::Result<()> {
         .init();
 
     let cli = cli::Cli::parse();
-    cli::dispatch(cli)
-        .await
-        .map_err(|e| miette::miette!("{e}"))
+    match cli::dispatch(cli).await {
+        Err(VoxputError::NoSpeech) => {
+            eprintln!("{}", VoxputError::NoSpeech);
+            std::process::exit(EXIT_NO_SPEECH);
+        }
+        result => result.map_err(|e| miette::miette!("{e}")),
+    }
 }
@@ -0,0 +1,203 @@
+use crate::audio::AudioData;
+
+/// Upload size cap for a single chunk, leaving headroom below the 25 MB
+/// request limit enforced by Groq and OpenAI.
+pub const MAX_UPLOAD_BYTES: usize = 24 * 1024 * 1024;
+
+/// Worst-case bytes per uploaded sample, whatever the codec. WAV stores 16-bit
+/// PCM; FLAC falls back to verbatim 16-bit samples when prediction does not
+/// help, and its few bytes of framing fit in the headroom of
+/// [`MAX_UPLOAD_BYTES`].
+const MAX_BYTES_PER_SAMPLE: usize = 2;
+
+/// Length of the frames compared when searching for a quiet cut point.
+const FRAME_SECS: f32 = 0.02;
+
+/// Upper bound on how far back from the hard limit a cut point is searched.
+const MAX_SEARCH_SECS: f32 = 30.0;
+
+/// How long recordings are split before upload.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChunkOptions {
+    /// Longest chunk sent in one request, in seconds. Chunks are also kept
+    /// under [`MAX_UPLOAD_BYTES`] whatever the sample rate.
+    pub max_chunk_secs: f32,
+    /// Audio shared by neighbouring chunks, in seconds, so words cut at a
+    /// boundary are heard whole by at least one request.
+    pub overlap_secs: f32,
+    /// Number of chunks transcribed at the same time.
+    pub concurrency: usize,
+}
+
+impl Default for ChunkOptions {
+    fn default() -> Self {
+        Self {
+            max_chunk_secs: 600.0,
+            overlap_secs: 2.0,
+            concurrency: 1,
+        }
+    }
+}
+
+impl ChunkOptions {
+    /// Largest chunk in samples at `sample_rate`.
+    fn max_samples(&self, sample_rate: u32) -> usize {
+        let by_time = (self.max_chunk_secs.max(1.0) * sample_rate as f32) as usize;
+        by_time.clamp(1, MAX_UPLOAD_BYTES / MAX_BYTES_PER_SAMPLE)
+    }
+}
+
+/// A slice of a longer recording.
+#[derive(Debug, Clone)]
+pub struct AudioChunk {
+    pub audio: AudioData,
+    /// Start of the chunk within the full recording, in seconds.
+    pub offset_secs: f64,
+    /// Length of audio shared with the previous chunk, in seconds (0 for the first).
+    pub overlap_secs: f64,
+}
+
+/// Split `audio` into chunks no longer than `opts` allows.
+///
+/// Each cut is placed at the quietest 20 ms frame in the last quarter of the
+/// allowed length (at most 30 s back), so cuts tend to land between words.
+/// Consecutive chunks share `opts.overlap_secs` of audio. Audio that already
+/// fits is returned as a single chunk.
+pub fn split(audio: &AudioData, opts: &ChunkOptions) -> Vec<AudioChunk> {
+    let rate = audio.sample_rate.max(1);
+    let total = audio.samples.len();
+    let max = opts.max_samples(rate);
+
+    if total <= max {
+        return vec![AudioChunk {
+            audio: audio.clone(),
+            offset_secs: 0.0,
+            overlap_secs: 0.0,
+        }];
+    }
+
+    let window = (max / 4).min((MAX_SEARCH_SECS * rate as f32) as usize);
+    let overlap = ((opts.overlap_secs.max(0.0) * rate as f32) as usize).min(max / 4);
+    let frame = ((FRAME_SECS * rate as f32) as usize).max(1);
+
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    let mut shared = 0;
+    loop {
+        let end = if total - start <= max {
+            total
+        } else {
+            let hard_end = start + max;
+            let search_from = hard_end - window;
+            search_from + quietest_point(&audio.samples[search_from..hard_end], frame)
+        };
+
+        chunks.push(AudioChunk {
+            audio: AudioData {
+                samples: audio.samples[start..end].to_vec(),
+                sample_rate: audio.sample_rate,
+                channels: audio.channels,
+            },
+            offset_secs: start as f64 / rate as f64,
+            overlap_secs: shared as f64 / rate as f64,
+        });
+
+        if end == total {
+            return chunks;
+        }
+        // The cut is at least `max - window` past `start`, so this always advances.
+        start = end - overlap;
+        shared = overlap;
+    }
+}
+
+/// Index of the centre of the lowest-energy frame in `samples`.
+/// Ties go to the later frame to keep chunks as long as possible.
+fn quietest_point(samples: &[f32], frame: usize) -> usize {
+    let mut best = (f32::INFINITY, samples.len() / 2);
+    for (i, chunk) in samples.chunks(frame).enumerate() {
+        let energy = chunk.iter().map(|s| s * s).sum::<f32>() / chunk.len() as f32;
+        if energy <= best.0 {
+            best = (energy, i * frame + chunk.len() / 2);
+        }
+    }
+    best.1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tone(secs: f32, rate: u32) -> Vec<f32> {
+        (0..(secs * rate as f32) as usize)
+            .map(|i| (i as f32 * 0.1).sin() * 0.5)
+            .collect()
+    }
+
+    fn opts(max_chunk_secs: f32, overlap_secs: f32) -> ChunkOptions {
+        ChunkOptions {
+            max_chunk_secs,
+            overlap_secs,
+            ..ChunkOptions::default()
+        }
+    }
+
+    #[test]
+    fn short_audio_is_a_single_chunk() {
+        let audio = AudioData {
+            samples: tone(5.0, 1000),
+            sample_rate: 1000,
+            channels: 1,
+        };
+        let chunks = split(&audio, &opts(10.0, 1.0));
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].audio.samples.len(), 5000);
+        assert_eq!(chunks[0].overlap_secs, 0.0);
+    }
+
+    #[test]
+    fn chunks_cover_audio_within_limit_and_overlap() {
+        let audio = AudioData {
+            samples: tone(95.0, 1000),
+            sample_rate: 1000,
+            channels: 1,
+        };
+        let chunks = split(&audio, &opts(20.0, 2.0));
+
+        assert!(chunks.len() >= 5);
+        for pair in chunks.windows(2) {
+            let prev_end = pair[0].offset_secs + pair[0].audio.duration_secs() as f64;
+            assert!((prev_end - pair[1].offset_secs - 2.0).abs() < 1e-3);
+            assert!((pair[1].overlap_secs - 2.0).abs() < 1e-3);
+        }
+        for chunk in &chunks {
+            assert!(chunk.audio.duration_secs() <= 20.0);
+        }
+        let last = chunks.last().unwrap();
+        let end = last.offset_secs + last.audio.duration_secs() as f64;
+        assert!((end - 95.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn cut_lands_in_silence() {
+        // 18 s of tone, 0.5 s of silence, then more tone; limit is 20 s.
+        let mut samples = tone(18.0, 1000);
+        samples.extend(vec![0.0; 500]);
+        samples.extend(tone(10.0, 1000));
+        let audio = AudioData {
+            samples,
+            sample_rate: 1000,
+            channels: 1,
+        };
+
+        let chunks = split(&audio, &opts(20.0, 0.0));
+        let cut = chunks[0].audio.duration_secs();
+        assert!((18.0..=18.5).contains(&cut), "cut at {cut}s");
+    }
+
+    #[test]
+    fn chunks_stay_under_upload_size_at_high_rates() {
+        let limit = ChunkOptions::default().max_samples(48000) * MAX_BYTES_PER_SAMPLE;
+        assert!(limit <= MAX_UPLOAD_BYTES);
+    }
+}
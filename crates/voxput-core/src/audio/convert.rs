@@ -0,0 +1,207 @@
+use cpal::{FromSample, Sample};
+use serde::Deserialize;
+
+/// Which input channel becomes the recording.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(try_from = "ChannelSetting")]
+pub enum ChannelSelect {
+    /// Average of every channel.
+    #[default]
+    Mix,
+    /// A single channel, counted from 0.
+    Index(usize),
+}
+
+impl std::str::FromStr for ChannelSelect {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.eq_ignore_ascii_case("mix") {
+            return Ok(Self::Mix);
+        }
+        s.parse()
+            .map(Self::Index)
+            .map_err(|_| format!("invalid channel {s:?}: expected a channel index or \"mix\""))
+    }
+}
+
+/// `audio.channel` as written in the config file: an index or a name.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ChannelSetting {
+    Index(usize),
+    Name(String),
+}
+
+impl TryFrom<ChannelSetting> for ChannelSelect {
+    type Error = String;
+
+    fn try_from(setting: ChannelSetting) -> Result<Self, Self::Error> {
+        match setting {
+            ChannelSetting::Index(i) => Ok(Self::Index(i)),
+            ChannelSetting::Name(name) => name.parse(),
+        }
+    }
+}
+
+/// How interleaved input channels are turned into mono.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ChannelMix {
+    pub channel: ChannelSelect,
+    /// Linear gain applied to each channel, by index; channels past the end
+    /// of the list are left at unity.
+    pub gains: Vec<f32>,
+}
+
+impl ChannelMix {
+    /// Fewest channels a stream needs to provide the selected channel.
+    pub fn min_channels(&self) -> usize {
+        match self.channel {
+            ChannelSelect::Mix => 1,
+            ChannelSelect::Index(i) => i + 1,
+        }
+    }
+
+    fn gain(&self, channel: usize) -> f32 {
+        self.gains.get(channel).copied().unwrap_or(1.0)
+    }
+}
+
+/// Interleaved `data` with `channels` channels as mono f32 chosen by `mix`:
+/// the selected channel, or the average of all of them, after each
+/// channel's gain. Gains can push samples past full scale; the result is
+/// clipped to `-1.0..=1.0`. A selected channel the data lacks reads as
+/// silence.
+///
+/// Works for every cpal sample type: signed integers map their full range
+/// onto `-1.0..1.0`, unsigned integers are centred on their midpoint first,
+/// and floats are passed through.
+pub fn downmix<'a, T>(
+    data: &'a [T],
+    channels: usize,
+    mix: &'a ChannelMix,
+) -> impl ExactSizeIterator<Item = f32> + 'a
+where
+    T: Sample,
+    f32: FromSample<T>,
+{
+    let channels = channels.max(1);
+    data.chunks_exact(channels).map(move |frame| {
+        let sample = match mix.channel {
+            ChannelSelect::Mix => {
+                frame
+                    .iter()
+                    .enumerate()
+                    .map(|(c, &s)| f32::from_sample(s) * mix.gain(c))
+                    .sum::<f32>()
+                    / channels as f32
+            }
+            ChannelSelect::Index(c) => frame
+                .get(c)
+                .map_or(0.0, |&s| f32::from_sample(s) * mix.gain(c)),
+        };
+        sample.clamp(-1.0, 1.0)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mono<T>(data: &[T]) -> Vec<f32>
+    where
+        T: Sample,
+        f32: FromSample<T>,
+    {
+        downmix(data, 1, &ChannelMix::default()).collect()
+    }
+
+    #[test]
+    fn i16_spans_full_range() {
+        assert_eq!(mono(&[i16::MIN, 0, 16384]), vec![-1.0, 0.0, 0.5]);
+        assert!((mono(&[i16::MAX])[0] - 1.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn u16_is_centred_on_midpoint() {
+        assert_eq!(mono(&[0u16, 32768, 49152]), vec![-1.0, 0.0, 0.5]);
+    }
+
+    #[test]
+    fn u8_and_i8_convert() {
+        assert_eq!(mono(&[0u8, 128, 192]), vec![-1.0, 0.0, 0.5]);
+        assert_eq!(mono(&[i8::MIN, 0, 64]), vec![-1.0, 0.0, 0.5]);
+    }
+
+    #[test]
+    fn i32_and_u32_convert() {
+        assert_eq!(mono(&[i32::MIN, 0, 1 << 30]), vec![-1.0, 0.0, 0.5]);
+        assert_eq!(mono(&[0u32, 1 << 31]), vec![-1.0, 0.0]);
+    }
+
+    #[test]
+    fn floats_pass_through() {
+        assert_eq!(mono(&[0.25f32, -0.75]), vec![0.25, -0.75]);
+        assert_eq!(mono(&[0.25f64, -0.75]), vec![0.25, -0.75]);
+    }
+
+    #[test]
+    fn interleaved_channels_are_averaged() {
+        let out: Vec<f32> =
+            downmix(&[i16::MIN, 0, 16384, 16384], 2, &ChannelMix::default()).collect();
+        assert_eq!(out, vec![-0.5, 0.5]);
+    }
+
+    #[test]
+    fn partial_trailing_frame_is_dropped() {
+        let out: Vec<f32> = downmix(&[0.2f32, 0.4, 0.6], 2, &ChannelMix::default()).collect();
+        assert_eq!(out.len(), 1);
+        assert!((out[0] - 0.3).abs() < 1e-6);
+    }
+
+    #[test]
+    fn selected_channel_is_kept_alone() {
+        let mix = ChannelMix {
+            channel: ChannelSelect::Index(1),
+            gains: Vec::new(),
+        };
+        let out: Vec<f32> = downmix(&[0.1f32, 0.5, 0.2, -0.25], 2, &mix).collect();
+        assert_eq!(out, vec![0.5, -0.25]);
+
+        let missing = ChannelMix {
+            channel: ChannelSelect::Index(2),
+            gains: Vec::new(),
+        };
+        assert_eq!(
+            downmix(&[0.1f32, 0.5], 2, &missing).collect::<Vec<_>>(),
+            vec![0.0]
+        );
+    }
+
+    #[test]
+    fn gains_apply_per_channel_and_clip() {
+        let mix = ChannelMix {
+            channel: ChannelSelect::Mix,
+            gains: vec![2.0, 0.0],
+        };
+        let out: Vec<f32> = downmix(&[0.25f32, 0.9, 0.75, 0.9], 2, &mix).collect();
+        assert_eq!(out, vec![0.25, 0.75]);
+
+        let loud = ChannelMix {
+            channel: ChannelSelect::Index(0),
+            gains: vec![4.0],
+        };
+        assert_eq!(
+            downmix(&[0.5f32, -0.5], 1, &loud).collect::<Vec<_>>(),
+            vec![1.0, -1.0]
+        );
+    }
+
+    #[test]
+    fn channel_parses_from_index_or_mix() {
+        assert_eq!("mix".parse(), Ok(ChannelSelect::Mix));
+        assert_eq!("MIX".parse(), Ok(ChannelSelect::Mix));
+        assert_eq!("3".parse(), Ok(ChannelSelect::Index(3)));
+        assert!("left".parse::<ChannelSelect>().is_err());
+    }
+}
@@ -1,141 +1,462 @@
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use cpal::{FromSample, SampleFormat, SizedSample};
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::{Arc, Mutex};
+use std::sync::mpsc::SyncSender;
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 
-use crate::audio::{AudioBackend, AudioData, DeviceInfo};
+use crate::audio::chunk::AudioChunk;
+use crate::audio::convert::{downmix, ChannelMix};
+use crate::audio::device::{device_id, parse_preferences, rank, DeviceName};
+use crate::audio::meter::{AudioLevel, LevelCallback};
+use crate::audio::resample::resample;
+use crate::audio::segment::{SegmentCallback, SegmentOptions, Segmenter};
+use crate::audio::stream::{self, AudioStream, RecordHooks, StreamHandle, StreamWriter, WarningCallback};
+use crate::audio::vad::VadOptions;
+use crate::audio::warm::WarmMic;
+use crate::audio::{AudioBackend, AudioData, ConfigRange, DeviceInfo, StreamConfigInfo};
 use crate::errors::{Result, VoxputError};
 
-pub struct CpalBackend;
+/// How often the capture thread checks whether it should close the device.
+const CAPTURE_POLL: Duration = Duration::from_millis(10);
+
+/// How long a device may go without delivering audio before it is treated
+/// as lost; some backends stop calling back on unplug instead of erroring.
+const STALL_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Captures from a cpal input device and delivers mono audio at `sample_rate`.
+///
+/// The device is opened at `sample_rate` when it supports it; otherwise at
+/// its default rate, and the recording is resampled afterwards. With
+/// [`with_source`](Self::with_source), another backend (a file or a
+/// generated signal) stands in for the device while everything else about
+/// recording stays the same.
+pub struct CpalBackend {
+    sample_rate: u32,
+    channel_mix: ChannelMix,
+    auto_stop: Option<VadOptions>,
+    on_level: Option<LevelCallback>,
+    on_segment: Option<(SegmentOptions, SegmentCallback)>,
+    warm_mic: Option<Arc<WarmMic>>,
+    source: Option<Arc<dyn AudioBackend>>,
+    on_warning: Option<WarningCallback>,
+}
+
+impl CpalBackend {
+    pub fn new(sample_rate: u32) -> Self {
+        Self {
+            sample_rate,
+            channel_mix: ChannelMix::default(),
+            auto_stop: None,
+            on_level: None,
+            on_segment: None,
+            warm_mic: None,
+            source: None,
+            on_warning: None,
+        }
+    }
+
+    /// Record the channel chosen by `mix`, or a mix of all of them, with
+    /// its per-channel gains; by default every channel is averaged.
+    pub fn with_channel_mix(mut self, mix: ChannelMix) -> Self {
+        self.channel_mix = mix;
+        self
+    }
+
+    /// End recordings once the speaker falls silent, as judged by a
+    /// [`VoiceActivityDetector`](crate::audio::vad::VoiceActivityDetector)
+    /// with these options.
+    pub fn with_auto_stop(mut self, vad: Option<VadOptions>) -> Self {
+        self.auto_stop = vad;
+        self
+    }
+
+    /// Report the input level about 20 times a second while recording.
+    ///
+    /// The callback runs on the thread calling `record`, so it should hand the
+    /// level off (print it, send it on a channel) rather than block.
+    pub fn with_level_callback(mut self, on_level: impl Fn(AudioLevel) + Send + Sync + 'static) -> Self {
+        self.on_level = Some(Box::new(on_level));
+        self
+    }
+
+    /// Split recordings at pauses as they are captured, handing each
+    /// finished segment (resampled to `sample_rate`) to `on_segment` while
+    /// recording goes on. The last segment arrives before `record` returns.
+    pub fn with_segments(
+        mut self,
+        opts: SegmentOptions,
+        on_segment: impl Fn(AudioChunk) + Send + Sync + 'static,
+    ) -> Self {
+        self.on_segment = Some((opts, Box::new(on_segment)));
+        self
+    }
+
+    /// Record from a microphone kept open between recordings, starting each
+    /// recording with the audio captured just before it. The warm mic's own
+    /// device is used; the `devices` passed to `record` are ignored.
+    pub fn with_warm_mic(mut self, warm_mic: Option<Arc<WarmMic>>) -> Self {
+        self.warm_mic = warm_mic;
+        self
+    }
+
+    /// Record from `source` instead of an input device, such as a
+    /// [`FileBackend`](crate::audio::file::FileBackend) on a machine without
+    /// a sound card. Level metering, auto-stop, segmenting and resampling
+    /// apply to it as they would to a device; the channel mix does not.
+    pub fn with_source(mut self, source: Option<Arc<dyn AudioBackend>>) -> Self {
+        self.source = source;
+        self
+    }
+
+    /// Report a recording cut short by a lost device, whose audio so far is
+    /// kept, or one that fell back to the default device because no
+    /// preferred device could be opened, to `on_warning` as well as the log.
+    pub fn with_warning_callback(mut self, on_warning: impl Fn(&str) + Send + Sync + 'static) -> Self {
+        self.on_warning = Some(Box::new(on_warning));
+        self
+    }
+}
+
+impl Default for CpalBackend {
+    fn default() -> Self {
+        Self::new(16000)
+    }
+}
 
 impl AudioBackend for CpalBackend {
     fn list_devices(&self) -> Result<Vec<DeviceInfo>> {
-        let host = cpal::default_host();
-        let default_name = host
-            .default_input_device()
-            .and_then(|d| d.name().ok())
-            .unwrap_or_default();
-
-        let devices = host
-            .input_devices()
-            .map_err(|e| VoxputError::Audio(format!("Failed to enumerate devices: {e}")))?
-            .filter_map(|d| d.name().ok().map(|name| DeviceInfo {
-                is_default: name == default_name,
-                name,
-            }))
-            .collect();
+        let default_host = cpal::default_host().id();
+        let mut devices = Vec::new();
+        for host_id in cpal::available_hosts() {
+            let host = match cpal::host_from_id(host_id) {
+                Ok(host) => host,
+                Err(e) => {
+                    tracing::debug!(host = host_id.name(), "Audio host unavailable: {e}");
+                    continue;
+                }
+            };
+            let default_name = (host_id == default_host)
+                .then(|| host.default_input_device().and_then(|d| d.name().ok()))
+                .flatten();
 
+            let inputs = match host.input_devices() {
+                Ok(inputs) => inputs,
+                Err(e) => {
+                    tracing::debug!(host = host_id.name(), "Could not enumerate input devices: {e}");
+                    continue;
+                }
+            };
+            for device in inputs {
+                let Ok(name) = device.name() else {
+                    continue;
+                };
+                let default_config = device.default_input_config().ok().map(|c| StreamConfigInfo {
+                    channels: c.channels(),
+                    sample_rate: c.sample_rate().0,
+                    sample_format: c.sample_format().to_string(),
+                });
+                let supported_configs: Vec<ConfigRange> = device
+                    .supported_input_configs()
+                    .map(|ranges| {
+                        ranges
+                            .map(|r| ConfigRange {
+                                channels: r.channels(),
+                                min_sample_rate: r.min_sample_rate().0,
+                                max_sample_rate: r.max_sample_rate().0,
+                                sample_format: r.sample_format().to_string(),
+                            })
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                let channels = supported_configs
+                    .iter()
+                    .map(|c| c.channels)
+                    .chain(default_config.as_ref().map(|c| c.channels))
+                    .max()
+                    .unwrap_or(0);
+                devices.push(DeviceInfo {
+                    id: device_id(host_id.name(), &name),
+                    host: host_id.name().to_string(),
+                    is_default: default_name.as_deref() == Some(name.as_str()),
+                    channels,
+                    default_config,
+                    supported_configs,
+                    name,
+                });
+            }
+        }
         Ok(devices)
     }
 
+    fn stream(&self, stop: Arc<AtomicBool>, devices: &[String]) -> Result<AudioStream> {
+        if let Some(warm_mic) = &self.warm_mic {
+            return warm_mic.stream(stop);
+        }
+        if let Some(source) = &self.source {
+            return source.stream(stop, devices);
+        }
+        // cpal streams cannot move between threads, so a dedicated thread
+        // opens the device and keeps the stream alive while it captures.
+        let devices = devices.to_vec();
+        let sample_rate = self.sample_rate;
+        let mix = self.channel_mix.clone();
+        let (ready_tx, ready_rx) = std::sync::mpsc::sync_channel(1);
+        std::thread::Builder::new()
+            .name("voxput-capture".into())
+            .spawn(move || capture(&devices, sample_rate, &mix, &stop, ready_tx))
+            .map_err(|e| VoxputError::Audio(format!("Failed to start capture thread: {e}")))?;
+        let (stream, fallback) = ready_rx
+            .recv()
+            .map_err(|_| VoxputError::Audio("Capture thread exited before opening the device".into()))??;
+        if let (Some(warning), Some(on_warning)) = (fallback, &self.on_warning) {
+            on_warning(&warning);
+        }
+        Ok(stream)
+    }
+
     fn record(
         &self,
         duration_secs: f32,
         stop: Arc<AtomicBool>,
-        device_name: Option<&str>,
+        devices: &[String],
     ) -> Result<AudioData> {
-        let host = cpal::default_host();
-
-        let device = match device_name {
-            Some(name) => host
-                .input_devices()
-                .map_err(|e| VoxputError::Audio(format!("Failed to enumerate devices: {e}")))?
-                .find(|d| d.name().ok().as_deref() == Some(name))
-                .ok_or_else(|| VoxputError::Audio(format!("Device '{name}' not found")))?,
-            None => host
-                .default_input_device()
-                .ok_or(VoxputError::NoDevice)?,
-        };
-
-        // Prefer 16 kHz mono; fall back to device default.
-        let config = select_config(&device)?;
-        let sample_rate = config.sample_rate().0;
-        let channels = config.channels();
-
-        let samples: Arc<Mutex<Vec<f32>>> = Arc::new(Mutex::new(Vec::new()));
-        let samples_writer = Arc::clone(&samples);
-
-        let err_flag: Arc<Mutex<Option<String>>> = Arc::new(Mutex::new(None));
-        let err_writer = Arc::clone(&err_flag);
-
-        let stream = device
-            .build_input_stream(
-                &config.into(),
-                move |data: &[f32], _: &cpal::InputCallbackInfo| {
-                    let mut buf = samples_writer.lock().unwrap();
-                    if channels == 1 {
-                        buf.extend_from_slice(data);
-                    } else {
-                        for chunk in data.chunks_exact(channels as usize) {
-                            let mono = chunk.iter().sum::<f32>() / channels as f32;
-                            buf.push(mono);
-                        }
-                    }
-                },
-                move |e| {
-                    *err_writer.lock().unwrap() = Some(e.to_string());
-                },
-                None,
-            )
-            .map_err(|e| VoxputError::Audio(format!("Failed to build input stream: {e}")))?;
-
-        stream
-            .play()
-            .map_err(|e| VoxputError::Audio(format!("Failed to start stream: {e}")))?;
-
-        // Poll every 50 ms; exit when stop flag is set or duration expires.
-        let max_duration = if duration_secs > 0.0 {
-            Some(Duration::from_secs_f32(duration_secs))
-        } else {
-            None
+        let stream = self.stream(Arc::clone(&stop), devices)?;
+        let target_rate = self.sample_rate;
+        let segmenter = self.on_segment.as_ref().map(|(opts, on_segment)| {
+            Segmenter::new(opts.clone(), stream.sample_rate(), move |segment: AudioChunk| {
+                on_segment(AudioChunk {
+                    audio: resample(&segment.audio, target_rate),
+                    ..segment
+                })
+            })
+        });
+        let hooks = RecordHooks {
+            auto_stop: self.auto_stop.as_ref(),
+            on_level: self.on_level.as_ref(),
+            segmenter,
+            on_warning: self.on_warning.as_ref(),
         };
-        let start = Instant::now();
+        let captured = stream.record(duration_secs, &stop, hooks)?;
+        if captured.sample_rate != self.sample_rate {
+            tracing::debug!(from = captured.sample_rate, to = self.sample_rate, "Resampling capture");
+        }
+        Ok(resample(&captured, self.sample_rate))
+    }
+}
 
-        loop {
-            std::thread::sleep(Duration::from_millis(50));
-            if stop.load(Ordering::Relaxed) {
-                break;
+/// Open the device and hand its stream, with the warning of any fallback to
+/// the default device, to `ready`; then keep capturing until `stop` is set,
+/// the reader is dropped or the device fails or stalls.
+fn capture(
+    devices: &[String],
+    sample_rate: u32,
+    mix: &ChannelMix,
+    stop: &AtomicBool,
+    ready: SyncSender<Result<(AudioStream, Option<String>)>>,
+) {
+    let (stream, handle) = match open_stream(devices, sample_rate, mix) {
+        Ok(((stream, reader, handle), fallback)) => {
+            if ready.send(Ok((reader, fallback))).is_err() {
+                return;
             }
-            if let Some(max) = max_duration {
-                if start.elapsed() >= max {
-                    stop.store(true, Ordering::Relaxed); // tell listener to exit too
-                    break;
+            (stream, handle)
+        }
+        Err(e) => {
+            let _ = ready.send(Err(e));
+            return;
+        }
+    };
+
+    let (mut received, mut last_audio) = (handle.received(), Instant::now());
+    while !stop.load(Ordering::Relaxed) && handle.is_active() {
+        std::thread::sleep(CAPTURE_POLL);
+        if handle.received() != received {
+            (received, last_audio) = (handle.received(), Instant::now());
+        } else if last_audio.elapsed() >= STALL_TIMEOUT {
+            handle.fail("input device stopped delivering audio; was it unplugged?");
+        }
+    }
+    // Dropping the stream drops its writer, which ends the reader's frames.
+    drop(stream);
+}
+
+/// Open the first of `devices` on any host that is available and opens
+/// cleanly (or the default input) and start a stream feeding a new [`AudioStream`] at the
+/// device's rate. A fallback to the default input is returned as a warning.
+fn open_stream(
+    devices: &[String],
+    sample_rate: u32,
+    mix: &ChannelMix,
+) -> Result<((cpal::Stream, AudioStream, StreamHandle), Option<String>)> {
+    let mut fallback = None;
+    if !devices.is_empty() {
+        let preferences = parse_preferences(devices)?;
+        let (names, inputs): (Vec<DeviceName>, Vec<cpal::Device>) = input_devices().into_iter().unzip();
+        for ranked in rank(&preferences, &names) {
+            let index = names.iter().position(|n| n == ranked).expect("ranked from available");
+            match open_device(&inputs[index], sample_rate, mix) {
+                Ok(opened) => {
+                    tracing::info!(device = ranked.id, "Opened input device");
+                    return Ok((opened, None));
                 }
+                Err(e) => tracing::warn!(device = ranked.id, "Could not open input device, trying the next one: {e}"),
             }
         }
+        let warning = format!(
+            "No preferred input device ({}) is available; using the default",
+            devices.join(", ")
+        );
+        tracing::warn!("{warning}");
+        fallback = Some(warning);
+    }
+
+    let device = cpal::default_host().default_input_device().ok_or(VoxputError::NoDevice)?;
+    Ok((open_device(&device, sample_rate, mix)?, fallback))
+}
 
-        drop(stream);
+/// Every input device on every available host, the default host's first.
+/// Hosts that cannot be opened or enumerated are skipped.
+fn input_devices() -> Vec<(DeviceName, cpal::Device)> {
+    let default_host = cpal::default_host().id();
+    let mut hosts = cpal::available_hosts();
+    hosts.sort_by_key(|&id| id != default_host);
 
-        if let Some(err) = err_flag.lock().unwrap().take() {
-            return Err(VoxputError::Audio(format!("Stream error during recording: {err}")));
+    let mut devices = Vec::new();
+    for host_id in hosts {
+        let inputs = cpal::host_from_id(host_id)
+            .map_err(|e| e.to_string())
+            .and_then(|host| host.input_devices().map_err(|e| e.to_string()));
+        match inputs {
+            Ok(inputs) => devices.extend(
+                inputs.filter_map(|d| d.name().ok().map(|name| (DeviceName::new(host_id.name(), name), d))),
+            ),
+            Err(e) => tracing::debug!(host = host_id.name(), "Skipping audio host: {e}"),
         }
+    }
+    devices
+}
 
-        let raw = Arc::try_unwrap(samples)
-            .map_err(|_| VoxputError::Audio("Failed to collect samples".into()))?
-            .into_inner()
-            .unwrap();
-
-        Ok(AudioData {
-            samples: raw,
-            sample_rate,
-            channels: 1,
-        })
+/// Start a stream on `device` feeding a new [`AudioStream`] at the device's rate.
+fn open_device(
+    device: &cpal::Device,
+    sample_rate: u32,
+    mix: &ChannelMix,
+) -> Result<(cpal::Stream, AudioStream, StreamHandle)> {
+    // Prefer the target rate; fall back to device default.
+    let config = select_config(device, sample_rate, mix.min_channels())?;
+    if (config.channels() as usize) < mix.min_channels() {
+        return Err(VoxputError::Audio(format!(
+            "Channel {} selected, but the device has only {} channel(s)",
+            mix.min_channels() - 1,
+            config.channels()
+        )));
     }
+    let sample_format = config.sample_format();
+    tracing::debug!(
+        sample_rate = config.sample_rate().0,
+        %sample_format,
+        channels = config.channels(),
+        "Opening input stream"
+    );
+
+    let (writer, reader) = stream::channel(config.sample_rate().0);
+    let handle = writer.handle();
+
+    let stream_config = config.into();
+    let stream = match sample_format {
+        SampleFormat::I8 => build_stream::<i8>(device, &stream_config, mix, writer),
+        SampleFormat::I16 => build_stream::<i16>(device, &stream_config, mix, writer),
+        SampleFormat::I32 => build_stream::<i32>(device, &stream_config, mix, writer),
+        SampleFormat::I64 => build_stream::<i64>(device, &stream_config, mix, writer),
+        SampleFormat::U8 => build_stream::<u8>(device, &stream_config, mix, writer),
+        SampleFormat::U16 => build_stream::<u16>(device, &stream_config, mix, writer),
+        SampleFormat::U32 => build_stream::<u32>(device, &stream_config, mix, writer),
+        SampleFormat::U64 => build_stream::<u64>(device, &stream_config, mix, writer),
+        SampleFormat::F32 => build_stream::<f32>(device, &stream_config, mix, writer),
+        SampleFormat::F64 => build_stream::<f64>(device, &stream_config, mix, writer),
+        other => Err(VoxputError::Audio(format!(
+            "Unsupported input sample format: {other}"
+        ))),
+    }?;
+
+    stream
+        .play()
+        .map_err(|e| VoxputError::Audio(format!("Failed to start stream: {e}")))?;
+
+    Ok((stream, reader, handle))
 }
 
-/// Try to get a 16 kHz mono f32 config; fall back to device default.
-fn select_config(device: &cpal::Device) -> Result<cpal::SupportedStreamConfig> {
-    let supported = device
+/// Build an input stream for samples of type `T`, pushing them into
+/// `writer` as mono f32 chosen by `mix` and reporting stream errors
+/// through it.
+fn build_stream<T>(
+    device: &cpal::Device,
+    config: &cpal::StreamConfig,
+    mix: &ChannelMix,
+    mut writer: StreamWriter,
+) -> Result<cpal::Stream>
+where
+    T: SizedSample,
+    f32: FromSample<T>,
+{
+    let channels = config.channels as usize;
+    let handle = writer.handle();
+    let mix = mix.clone();
+
+    device
+        .build_input_stream(
+            config,
+            move |data: &[T], _: &cpal::InputCallbackInfo| {
+                writer.push(downmix(data, channels, &mix));
+            },
+            move |e| handle.fail(e.to_string()),
+            None,
+        )
+        .map_err(|e| VoxputError::Audio(format!("Failed to build input stream: {e}")))
+}
+
+/// Sample formats in order of preference: native float first, then the
+/// integer formats with the most precision that devices commonly offer.
+const FORMAT_PREFERENCE: &[SampleFormat] = &[
+    SampleFormat::F32,
+    SampleFormat::I32,
+    SampleFormat::I16,
+    SampleFormat::F64,
+    SampleFormat::I64,
+    SampleFormat::U32,
+    SampleFormat::U16,
+    SampleFormat::I8,
+    SampleFormat::U8,
+    SampleFormat::U64,
+];
+
+/// Pick a config at `sample_rate` with at least `min_channels` channels, in
+/// the most preferred sample format the device supports at that rate; fall
+/// back to the device default.
+fn select_config(
+    device: &cpal::Device,
+    sample_rate: u32,
+    min_channels: usize,
+) -> Result<cpal::SupportedStreamConfig> {
+    let supported: Vec<_> = device
         .supported_input_configs()
-        .map_err(|e| VoxputError::Audio(format!("Failed to query configs: {e}")))?;
-
-    for range in supported {
-        if range.sample_format() == cpal::SampleFormat::F32
-            && range.min_sample_rate().0 <= 16000
-            && range.max_sample_rate().0 >= 16000
-        {
-            return Ok(range.with_sample_rate(cpal::SampleRate(16000)));
-        }
+        .map_err(|e| VoxputError::Audio(format!("Failed to query configs: {e}")))?
+        .filter(|range| {
+            range.min_sample_rate().0 <= sample_rate
+                && range.max_sample_rate().0 >= sample_rate
+                && range.channels() as usize >= min_channels
+        })
+        .collect();
+
+    let best = supported.into_iter().min_by_key(|range| {
+        FORMAT_PREFERENCE
+            .iter()
+            .position(|&f| f == range.sample_format())
+            .unwrap_or(FORMAT_PREFERENCE.len())
+    });
+    if let Some(range) = best {
+        return Ok(range.with_sample_rate(cpal::SampleRate(sample_rate)));
     }
 
     device
@@ -0,0 +1,178 @@
+use std::io::Cursor;
+use std::path::Path;
+
+use symphonia::core::audio::SampleBuffer;
+use symphonia::core::codecs::{DecoderOptions, CODEC_TYPE_NULL};
+use symphonia::core::errors::Error as SymphoniaError;
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::{MediaSource, MediaSourceStream};
+use symphonia::core::meta::MetadataOptions;
+use symphonia::core::probe::Hint;
+
+use crate::audio::AudioData;
+use crate::errors::{Result, VoxputError};
+
+/// Decode an audio file (WAV, FLAC, MP3 or Ogg Vorbis) into mono `AudioData`.
+///
+/// The file extension is used as a hint, but the container is detected from
+/// its contents. Multi-channel audio is downmixed by averaging channels; the
+/// sample rate is left as-is (see [`resample`](crate::audio::resample)).
+pub fn decode_file(path: &Path) -> Result<AudioData> {
+    let file = std::fs::File::open(path)
+        .map_err(|e| VoxputError::Audio(format!("Cannot open {}: {e}", path.display())))?;
+
+    let mut hint = Hint::new();
+    if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
+        hint.with_extension(ext);
+    }
+
+    decode(Box::new(file), &hint)
+        .map_err(|e| VoxputError::Audio(format!("Cannot decode {}: {e}", path.display())))
+}
+
+/// Decode in-memory audio file contents into mono `AudioData`.
+pub fn decode_bytes(bytes: Vec<u8>) -> Result<AudioData> {
+    decode(Box::new(Cursor::new(bytes)), &Hint::new())
+        .map_err(|e| VoxputError::Audio(format!("Cannot decode audio: {e}")))
+}
+
+fn decode(source: Box<dyn MediaSource>, hint: &Hint) -> std::result::Result<AudioData, String> {
+    let stream = MediaSourceStream::new(source, Default::default());
+    let probed = symphonia::default::get_probe()
+        .format(
+            hint,
+            stream,
+            &FormatOptions::default(),
+            &MetadataOptions::default(),
+        )
+        .map_err(|e| format!("unsupported or corrupt audio ({e})"))?;
+    let mut format = probed.format;
+
+    let track = format
+        .tracks()
+        .iter()
+        .find(|t| t.codec_params.codec != CODEC_TYPE_NULL)
+        .ok_or("no audio track found")?;
+    let track_id = track.id;
+    let mut sample_rate = track.codec_params.sample_rate;
+
+    let mut decoder = symphonia::default::get_codecs()
+        .make(&track.codec_params, &DecoderOptions::default())
+        .map_err(|e| format!("unsupported codec ({e})"))?;
+
+    let mut samples = Vec::new();
+    loop {
+        let packet = match format.next_packet() {
+            Ok(packet) => packet,
+            // Symphonia signals end-of-stream as an unexpected EOF.
+            Err(SymphoniaError::IoError(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof => {
+                break
+            }
+            Err(e) => return Err(e.to_string()),
+        };
+        if packet.track_id() != track_id {
+            continue;
+        }
+
+        let decoded = match decoder.decode(&packet) {
+            Ok(decoded) => decoded,
+            // A corrupt packet is skipped rather than failing the whole file.
+            Err(SymphoniaError::DecodeError(e)) => {
+                tracing::warn!("Skipping undecodable packet: {e}");
+                continue;
+            }
+            Err(e) => return Err(e.to_string()),
+        };
+
+        let spec = *decoded.spec();
+        sample_rate.get_or_insert(spec.rate);
+        let channels = spec.channels.count().max(1);
+
+        let mut buf = SampleBuffer::<f32>::new(decoded.capacity() as u64, spec);
+        buf.copy_interleaved_ref(decoded);
+        if channels == 1 {
+            samples.extend_from_slice(buf.samples());
+        } else {
+            for frame in buf.samples().chunks_exact(channels) {
+                samples.push(frame.iter().sum::<f32>() / channels as f32);
+            }
+        }
+    }
+
+    Ok(AudioData {
+        samples,
+        sample_rate: sample_rate.ok_or("unknown sample rate")?,
+        channels: 1,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::wav::encode_wav;
+
+    const VOICE_FIXTURE: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../../tests/fixtures/hello_world.wav"
+    );
+
+    #[test]
+    fn decodes_encoded_wav() {
+        let audio = AudioData {
+            samples: (0..1600).map(|i| (i as f32 * 0.05).sin() * 0.5).collect(),
+            sample_rate: 16000,
+            channels: 1,
+        };
+        let decoded = decode_bytes(encode_wav(&audio).unwrap()).unwrap();
+
+        assert_eq!(decoded.sample_rate, 16000);
+        assert_eq!(decoded.samples.len(), 1600);
+        for (a, b) in audio.samples.iter().zip(&decoded.samples) {
+            assert!((a - b).abs() < 1e-3, "{a} vs {b}");
+        }
+    }
+
+    #[test]
+    fn stereo_is_downmixed_to_mono() {
+        let spec = hound::WavSpec {
+            channels: 2,
+            sample_rate: 44100,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut cursor = Cursor::new(Vec::new());
+        {
+            let mut writer = hound::WavWriter::new(&mut cursor, spec).unwrap();
+            for _ in 0..441 {
+                writer.write_sample(i16::MAX / 2).unwrap();
+                writer.write_sample(0i16).unwrap();
+            }
+            writer.finalize().unwrap();
+        }
+
+        let decoded = decode_bytes(cursor.into_inner()).unwrap();
+        assert_eq!(decoded.sample_rate, 44100);
+        assert_eq!(decoded.channels, 1);
+        assert_eq!(decoded.samples.len(), 441);
+        assert!((decoded.samples[0] - 0.25).abs() < 1e-3);
+    }
+
+    #[test]
+    fn decodes_voice_fixture_from_disk() {
+        let audio = decode_file(Path::new(VOICE_FIXTURE)).unwrap();
+        assert_eq!(audio.sample_rate, 16000);
+        assert!(audio.duration_secs() > 2.0);
+    }
+
+    #[test]
+    fn garbage_is_rejected() {
+        let err = decode_bytes(b"definitely not audio".to_vec()).unwrap_err();
+        assert!(err.to_string().contains("Cannot decode"), "{err}");
+    }
+
+    #[test]
+    fn missing_file_names_path() {
+        let err = decode_file(Path::new("/nonexistent/clip.flac")).unwrap_err();
+        assert!(err.to_string().contains("/nonexistent/clip.flac"), "{err}");
+    }
+}
@@ -0,0 +1,179 @@
+use regex::Regex;
+
+use crate::errors::{Result, VoxputError};
+
+/// One entry of the `audio.device` preference list.
+#[derive(Debug, Clone)]
+pub enum DevicePattern {
+    /// Matches device names containing the text, ignoring case.
+    Substring(String),
+    /// Matches device names the expression finds a match in; written as
+    /// `/expression/`.
+    Regex(Regex),
+}
+
+impl DevicePattern {
+    /// Parse `/expression/` as a regular expression and anything else as
+    /// a substring.
+    pub fn parse(pattern: &str) -> Result<Self> {
+        match pattern.strip_prefix('/').and_then(|p| p.strip_suffix('/')) {
+            Some(expr) => Regex::new(expr).map(DevicePattern::Regex).map_err(|e| {
+                VoxputError::Config(format!("Invalid device pattern '{pattern}': {e}"))
+            }),
+            None => Ok(DevicePattern::Substring(pattern.to_lowercase())),
+        }
+    }
+
+    pub fn matches(&self, name: &str) -> bool {
+        match self {
+            DevicePattern::Substring(text) => name.to_lowercase().contains(text.as_str()),
+            DevicePattern::Regex(re) => re.is_match(name),
+        }
+    }
+
+    /// Whether `name` is exactly what this pattern spells out, which ranks
+    /// it ahead of other devices the pattern merely contains.
+    fn is_exact(&self, name: &str) -> bool {
+        matches!(self, DevicePattern::Substring(text) if name.to_lowercase() == *text)
+    }
+}
+
+/// An input device as [`rank`] sees it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeviceName {
+    /// Stable identifier, `<host>:<name>` (see [`device_id`]).
+    pub id: String,
+    pub name: String,
+}
+
+impl DeviceName {
+    pub fn new(host: &str, name: impl Into<String>) -> Self {
+        let name = name.into();
+        Self {
+            id: device_id(host, &name),
+            name,
+        }
+    }
+
+    /// Whether `pattern` picks this device: by its name, or by its whole id.
+    fn matched_by(&self, pattern: &DevicePattern) -> bool {
+        pattern.matches(&self.name) || pattern.is_exact(&self.id)
+    }
+}
+
+/// Stable identifier of the device `name` on the audio host `host`, as
+/// shown by `voxput devices` and accepted in `audio.device`.
+pub fn device_id(host: &str, name: &str) -> String {
+    format!("{host}:{name}")
+}
+
+/// Parse every entry of a device preference list.
+pub fn parse_preferences(preferences: &[String]) -> Result<Vec<DevicePattern>> {
+    preferences
+        .iter()
+        .map(|p| DevicePattern::parse(p))
+        .collect()
+}
+
+/// The `available` devices matching any of `preferences`, best first:
+/// devices matching an earlier pattern come before those matching a later
+/// one, and an exact name or id before a partial match of the same pattern.
+/// Otherwise devices keep their order in `available`.
+pub fn rank<'a>(preferences: &[DevicePattern], available: &'a [DeviceName]) -> Vec<&'a DeviceName> {
+    let mut ranked: Vec<&DeviceName> = Vec::new();
+    for pattern in preferences {
+        let mut matching: Vec<&DeviceName> = available
+            .iter()
+            .filter(|device| device.matched_by(pattern) && !ranked.contains(device))
+            .collect();
+        matching.sort_by_key(|device| {
+            !(pattern.is_exact(&device.name) || pattern.is_exact(&device.id))
+        });
+        ranked.extend(matching);
+    }
+    ranked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(names: &[&str]) -> Vec<String> {
+        names.iter().map(|s| s.to_string()).collect()
+    }
+
+    fn alsa(names: &[&str]) -> Vec<DeviceName> {
+        names
+            .iter()
+            .map(|name| DeviceName::new("ALSA", *name))
+            .collect()
+    }
+
+    fn prefs(patterns: &[&str]) -> Vec<DevicePattern> {
+        parse_preferences(&names(patterns)).unwrap()
+    }
+
+    fn ranked_names(ranked: Vec<&DeviceName>) -> Vec<&str> {
+        ranked.into_iter().map(|d| d.name.as_str()).collect()
+    }
+
+    #[test]
+    fn substrings_match_ignoring_case() {
+        let pattern = DevicePattern::parse("jabra").unwrap();
+        assert!(pattern.matches("Jabra Evolve 65 Mono"));
+        assert!(!pattern.matches("Built-in Audio Analog Stereo"));
+    }
+
+    #[test]
+    fn slashes_make_a_regex() {
+        let pattern = DevicePattern::parse(r"/^hw:CARD=USB\w*,DEV=0$/").unwrap();
+        assert!(pattern.matches("hw:CARD=USBMic,DEV=0"));
+        assert!(!pattern.matches("plughw:CARD=USBMic,DEV=0"));
+        assert!(DevicePattern::parse("/[unclosed/").is_err());
+    }
+
+    #[test]
+    fn devices_are_ranked_by_preference_order() {
+        let available = alsa(&[
+            "Built-in Mic",
+            "USB Audio Monitor",
+            "Jabra Evolve",
+            "USB Audio",
+        ]);
+        let ranked = rank(&prefs(&["jabra", "USB Audio", "built-in"]), &available);
+        assert_eq!(
+            ranked_names(ranked),
+            vec![
+                "Jabra Evolve",
+                "USB Audio",
+                "USB Audio Monitor",
+                "Built-in Mic"
+            ]
+        );
+    }
+
+    #[test]
+    fn unplugged_preferences_fall_through() {
+        let available = alsa(&["Built-in Mic"]);
+        assert_eq!(
+            ranked_names(rank(&prefs(&["jabra", "built-in"]), &available)),
+            vec!["Built-in Mic"]
+        );
+        assert!(rank(&prefs(&["jabra"]), &available).is_empty());
+    }
+
+    #[test]
+    fn ids_select_a_device_on_a_specific_host() {
+        let available = vec![
+            DeviceName::new("ALSA", "system"),
+            DeviceName::new("JACK", "system"),
+            DeviceName::new("JACK", "Jabra"),
+        ];
+        let ranked = rank(&prefs(&["JACK:system"]), &available);
+        assert_eq!(ranked, vec![&available[1]]);
+
+        // Only the whole id selects by host; names still match across hosts.
+        assert!(rank(&prefs(&["JACK"]), &available).is_empty());
+        assert_eq!(rank(&prefs(&["system"]), &available).len(), 2);
+    }
+}
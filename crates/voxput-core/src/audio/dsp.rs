@@ -0,0 +1,303 @@
+use std::f32::consts::TAU;
+
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+use crate::audio::vad::level_db;
+use crate::audio::AudioData;
+
+/// Length of the frames the noise gate opens and closes on.
+const GATE_FRAME_SECS: f32 = 0.01;
+
+/// How long the gate stays open after the level drops, so word endings
+/// and short pauses are not chopped.
+const GATE_HOLD_SECS: f32 = 0.15;
+
+/// Ceiling for loudness normalization, so boosting the average level does
+/// not clip the peaks.
+const LOUDNESS_PEAK_CEILING_DB: f32 = -1.0;
+
+/// How recordings are brought to a consistent level.
+#[derive(Debug, Clone, Copy, ValueEnum, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Normalize {
+    /// Leave the level as captured.
+    Off,
+    /// Scale so the loudest sample reaches the target (default -1 dBFS).
+    #[default]
+    Peak,
+    /// Scale so the RMS level reaches the target (default -20 dBFS),
+    /// limited so peaks stay below -1 dBFS.
+    Loudness,
+}
+
+impl Normalize {
+    /// Target level used when `audio.dsp.target_db` is not set.
+    pub fn default_target_db(self) -> f32 {
+        match self {
+            Normalize::Off | Normalize::Peak => -1.0,
+            Normalize::Loudness => -20.0,
+        }
+    }
+}
+
+/// Processing applied to recordings before upload (`[audio.dsp]` in the
+/// config file). Stages run in order: high-pass, noise gate, normalization.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DspOptions {
+    /// High-pass cutoff in Hz, removing DC offset and low rumble; `None` disables.
+    pub high_pass_hz: Option<f32>,
+    /// Level in dBFS below which audio is muted; `None` disables the gate.
+    pub noise_gate_db: Option<f32>,
+    pub normalize: Normalize,
+    /// Normalization target in dBFS; `None` uses the mode's default.
+    pub target_db: Option<f32>,
+    /// Most gain normalization may apply, in dB, so near-silence is not
+    /// boosted into loud noise.
+    pub max_gain_db: f32,
+}
+
+impl Default for DspOptions {
+    fn default() -> Self {
+        Self {
+            high_pass_hz: Some(80.0),
+            noise_gate_db: None,
+            normalize: Normalize::default(),
+            target_db: None,
+            max_gain_db: 20.0,
+        }
+    }
+}
+
+/// Run the configured stages over `audio` in place.
+pub fn process(audio: &mut AudioData, opts: &DspOptions) {
+    let rate = audio.sample_rate;
+    if let Some(cutoff) = opts.high_pass_hz {
+        high_pass(&mut audio.samples, rate, cutoff);
+    }
+    if let Some(threshold) = opts.noise_gate_db {
+        noise_gate(&mut audio.samples, rate, threshold);
+    }
+    let target = opts.target_db.unwrap_or(opts.normalize.default_target_db());
+    match opts.normalize {
+        Normalize::Off => {}
+        Normalize::Peak => normalize_peak(&mut audio.samples, target, opts.max_gain_db),
+        Normalize::Loudness => normalize_loudness(&mut audio.samples, target, opts.max_gain_db),
+    }
+}
+
+/// Second-order Butterworth high-pass filter at `cutoff_hz`.
+///
+/// Removes DC offset entirely and attenuates rumble and handling noise
+/// below the cutoff by 12 dB per octave, leaving speech untouched.
+pub fn high_pass(samples: &mut [f32], sample_rate: u32, cutoff_hz: f32) {
+    if sample_rate == 0 || cutoff_hz <= 0.0 || cutoff_hz >= sample_rate as f32 / 2.0 {
+        return;
+    }
+    // RBJ audio EQ cookbook coefficients with Q = 1/√2.
+    let w0 = TAU * cutoff_hz / sample_rate as f32;
+    let alpha = w0.sin() / std::f32::consts::SQRT_2;
+    let cos = w0.cos();
+    let a0 = 1.0 + alpha;
+    let b0 = (1.0 + cos) / 2.0 / a0;
+    let b1 = -(1.0 + cos) / a0;
+    let b2 = b0;
+    let a1 = -2.0 * cos / a0;
+    let a2 = (1.0 - alpha) / a0;
+
+    let (mut x1, mut x2, mut y1, mut y2) = (0.0, 0.0, 0.0, 0.0);
+    for s in samples.iter_mut() {
+        let x = *s;
+        let y = b0 * x + b1 * x1 + b2 * x2 - a1 * y1 - a2 * y2;
+        (x2, x1, y2, y1) = (x1, x, y1, y);
+        *s = y;
+    }
+}
+
+/// Mute stretches whose level stays below `threshold_db`.
+///
+/// The gate opens on any 10 ms frame at or above the threshold and closes
+/// again `GATE_HOLD_SECS` after the last one; gain changes are ramped over
+/// a frame so opening and closing do not click.
+pub fn noise_gate(samples: &mut [f32], sample_rate: u32, threshold_db: f32) {
+    let frame_len = ((sample_rate as f32 * GATE_FRAME_SECS) as usize).max(1);
+    let hold_frames = (GATE_HOLD_SECS / GATE_FRAME_SECS).ceil() as usize;
+
+    let mut since_open = usize::MAX;
+    let mut gain = 0.0f32;
+    for frame in samples.chunks_mut(frame_len) {
+        since_open = if level_db(frame) >= threshold_db {
+            0
+        } else {
+            since_open.saturating_add(1)
+        };
+        let target = if since_open <= hold_frames { 1.0 } else { 0.0 };
+        let step = (target - gain) / frame.len() as f32;
+        for s in frame.iter_mut() {
+            gain += step;
+            *s *= gain;
+        }
+        gain = target;
+    }
+}
+
+/// Scale `samples` so the largest magnitude reaches `target_db` dBFS,
+/// applying at most `max_gain_db` of gain.
+pub fn normalize_peak(samples: &mut [f32], target_db: f32, max_gain_db: f32) {
+    let peak = samples.iter().fold(0.0f32, |m, s| m.max(s.abs()));
+    if peak > 0.0 {
+        apply_gain(samples, target_db - 20.0 * peak.log10(), max_gain_db);
+    }
+}
+
+/// Scale `samples` so their RMS level reaches `target_db` dBFS, applying at
+/// most `max_gain_db` of gain and keeping peaks below -1 dBFS.
+pub fn normalize_loudness(samples: &mut [f32], target_db: f32, max_gain_db: f32) {
+    let level = level_db(samples);
+    let peak = samples.iter().fold(0.0f32, |m, s| m.max(s.abs()));
+    if !level.is_finite() || peak <= 0.0 {
+        return;
+    }
+    let headroom = LOUDNESS_PEAK_CEILING_DB - 20.0 * peak.log10();
+    apply_gain(samples, (target_db - level).min(headroom), max_gain_db);
+}
+
+fn apply_gain(samples: &mut [f32], gain_db: f32, max_gain_db: f32) {
+    let gain = 10f32.powf(gain_db.min(max_gain_db) / 20.0);
+    tracing::debug!(gain_db = gain_db.min(max_gain_db), "Normalizing");
+    for s in samples.iter_mut() {
+        *s *= gain;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RATE: u32 = 16000;
+
+    fn sine(freq: f32, secs: f32, amplitude: f32) -> Vec<f32> {
+        (0..(secs * RATE as f32) as usize)
+            .map(|i| (TAU * freq * i as f32 / RATE as f32).sin() * amplitude)
+            .collect()
+    }
+
+    fn peak(samples: &[f32]) -> f32 {
+        samples.iter().fold(0.0f32, |m, s| m.max(s.abs()))
+    }
+
+    /// Level of the second half, once filters have settled.
+    fn settled_db(samples: &[f32]) -> f32 {
+        level_db(&samples[samples.len() / 2..])
+    }
+
+    #[test]
+    fn high_pass_removes_dc_offset() {
+        let mut samples: Vec<f32> = sine(440.0, 1.0, 0.2).iter().map(|s| s + 0.3).collect();
+        high_pass(&mut samples, RATE, 80.0);
+        let tail = &samples[RATE as usize / 2..];
+        let mean = tail.iter().sum::<f32>() / tail.len() as f32;
+        assert!(mean.abs() < 1e-3, "mean {mean}");
+    }
+
+    #[test]
+    fn high_pass_keeps_speech_band_and_cuts_rumble() {
+        let mut voice = sine(1000.0, 1.0, 0.5);
+        high_pass(&mut voice, RATE, 80.0);
+        assert!((settled_db(&voice) - level_db(&sine(1000.0, 1.0, 0.5))).abs() < 0.1);
+
+        let mut rumble = sine(20.0, 1.0, 0.5);
+        high_pass(&mut rumble, RATE, 80.0);
+        // Two octaves below the cutoff: about 24 dB down.
+        assert!(settled_db(&rumble) < level_db(&sine(20.0, 1.0, 0.5)) - 20.0);
+    }
+
+    #[test]
+    fn noise_gate_mutes_quiet_stretches_only() {
+        let mut samples = [
+            sine(300.0, 0.5, 0.001),
+            sine(300.0, 0.5, 0.3),
+            sine(300.0, 0.5, 0.001),
+        ]
+        .concat();
+        noise_gate(&mut samples, RATE, -45.0);
+        let half = RATE as usize / 2;
+        assert!(
+            samples[..half].iter().all(|&s| s == 0.0),
+            "leading hiss muted"
+        );
+        assert!(
+            (level_db(&samples[half..2 * half]) - level_db(&sine(300.0, 0.5, 0.3))).abs() < 0.5
+        );
+        // Held open briefly after the speech, then closed.
+        assert!(samples[2 * half..2 * half + 800].iter().any(|&s| s != 0.0));
+        assert!(samples[2 * half + 4000..].iter().all(|&s| s == 0.0));
+    }
+
+    #[test]
+    fn noise_gate_ramps_instead_of_clicking() {
+        let mut samples = [vec![0.0; 1600], vec![0.5; 1600]].concat();
+        noise_gate(&mut samples, RATE, -30.0);
+        let jumps = samples
+            .windows(2)
+            .map(|w| (w[1] - w[0]).abs())
+            .fold(0.0f32, f32::max);
+        assert!(jumps < 0.01, "largest step {jumps}");
+    }
+
+    #[test]
+    fn peak_normalization_reaches_target() {
+        let mut samples = sine(440.0, 0.5, 0.1);
+        normalize_peak(&mut samples, -1.0, 30.0);
+        assert!((20.0 * peak(&samples).log10() + 1.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn normalization_gain_is_capped() {
+        let mut samples = sine(440.0, 0.5, 0.001);
+        normalize_peak(&mut samples, -1.0, 20.0);
+        assert!((peak(&samples) - 0.01).abs() < 1e-4, "only 20 dB applied");
+    }
+
+    #[test]
+    fn loudness_normalization_reaches_target_without_clipping() {
+        let mut samples = sine(440.0, 0.5, 0.05);
+        normalize_loudness(&mut samples, -20.0, 30.0);
+        assert!(
+            (level_db(&samples) + 20.0).abs() < 0.05,
+            "{}",
+            level_db(&samples)
+        );
+
+        // A spiky signal is limited by its peak instead.
+        let mut spiky = vec![0.001; 8000];
+        spiky[100] = 0.5;
+        normalize_loudness(&mut spiky, -20.0, 30.0);
+        assert!(20.0 * peak(&spiky).log10() <= -0.99);
+    }
+
+    #[test]
+    fn silence_is_left_alone() {
+        let mut samples = vec![0.0; 1600];
+        normalize_peak(&mut samples, -1.0, 20.0);
+        normalize_loudness(&mut samples, -20.0, 20.0);
+        assert!(samples.iter().all(|&s| s == 0.0));
+    }
+
+    #[test]
+    fn default_chain_brings_quiet_offset_speech_to_full_scale() {
+        let samples = sine(440.0, 1.0, 0.05).iter().map(|s| s + 0.1).collect();
+        let mut audio = AudioData {
+            samples,
+            sample_rate: RATE,
+            channels: 1,
+        };
+        process(&mut audio, &DspOptions::default());
+        let tail = &audio.samples[RATE as usize / 2..];
+        assert!(
+            tail.iter().sum::<f32>().abs() / (tail.len() as f32) < 1e-3,
+            "DC removed"
+        );
+        assert!(peak(&audio.samples) > 0.85, "peak {}", peak(&audio.samples));
+    }
+}
@@ -0,0 +1,103 @@
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+use crate::audio::{flac, wav, AudioData};
+use crate::errors::Result;
+
+/// Audio encoded for upload, with the metadata providers need for the
+/// multipart file part.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EncodedAudio {
+    pub bytes: Vec<u8>,
+    /// File name sent with the upload; providers detect the format from its extension.
+    pub file_name: &'static str,
+    pub mime_type: &'static str,
+}
+
+impl EncodedAudio {
+    /// Wrap bytes that are already a WAV file.
+    pub fn wav(bytes: Vec<u8>) -> Self {
+        Self {
+            bytes,
+            file_name: "audio.wav",
+            mime_type: "audio/wav",
+        }
+    }
+
+    /// Wrap bytes that are already a FLAC file.
+    pub fn flac(bytes: Vec<u8>) -> Self {
+        Self {
+            bytes,
+            file_name: "audio.flac",
+            mime_type: "audio/flac",
+        }
+    }
+}
+
+/// Turns captured audio into an uploadable file.
+pub trait AudioEncoder: Send + Sync {
+    fn encode(&self, audio: &AudioData) -> Result<EncodedAudio>;
+}
+
+/// 16-bit PCM WAV: no compression, accepted everywhere.
+pub struct WavEncoder;
+
+impl AudioEncoder for WavEncoder {
+    fn encode(&self, audio: &AudioData) -> Result<EncodedAudio> {
+        wav::encode_wav(audio).map(EncodedAudio::wav)
+    }
+}
+
+/// Lossless FLAC: roughly half the size of WAV for speech.
+pub struct FlacEncoder;
+
+impl AudioEncoder for FlacEncoder {
+    fn encode(&self, audio: &AudioData) -> Result<EncodedAudio> {
+        flac::encode_flac(audio).map(EncodedAudio::flac)
+    }
+}
+
+/// Codec used for uploads (`audio.upload_codec` in the config file).
+#[derive(Debug, Clone, Copy, ValueEnum, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum UploadCodec {
+    /// Lossless FLAC.
+    Flac,
+    /// Uncompressed 16-bit WAV.
+    #[default]
+    Wav,
+}
+
+impl UploadCodec {
+    pub fn encoder(self) -> Box<dyn AudioEncoder> {
+        match self {
+            UploadCodec::Flac => Box::new(FlacEncoder),
+            UploadCodec::Wav => Box::new(WavEncoder),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encoders_label_their_output() {
+        let audio = AudioData {
+            samples: vec![0.1; 1600],
+            sample_rate: 16000,
+            channels: 1,
+        };
+
+        let flac = UploadCodec::Flac.encoder().encode(&audio).unwrap();
+        assert_eq!(&flac.bytes[..4], b"fLaC");
+        assert_eq!(
+            (flac.file_name, flac.mime_type),
+            ("audio.flac", "audio/flac")
+        );
+
+        let wav = UploadCodec::Wav.encoder().encode(&audio).unwrap();
+        assert_eq!(&wav.bytes[..4], b"RIFF");
+        assert_eq!((wav.file_name, wav.mime_type), ("audio.wav", "audio/wav"));
+    }
+}
@@ -0,0 +1,128 @@
+use std::path::PathBuf;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
+use crate::audio::decode::decode_file;
+use crate::audio::source::{replay, virtual_device};
+use crate::audio::stream::AudioStream;
+use crate::audio::{AudioBackend, DeviceInfo};
+use crate::errors::Result;
+
+/// "Records" from an audio file, delivering it as a device would.
+///
+/// The file is decoded up front (any format [`decode_file`] reads) and
+/// played back at `speed` times real time, or as fast as it is read with a
+/// speed of 0. A recording ends at the end of the file, or earlier when its
+/// stop flag is set or its duration is reached. `devices` is ignored.
+pub struct FileBackend {
+    path: PathBuf,
+    speed: f32,
+}
+
+impl FileBackend {
+    /// Play `path` back in real time.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            speed: 1.0,
+        }
+    }
+
+    /// Play back `speed` times faster than real time; 0 removes the pacing.
+    pub fn with_speed(mut self, speed: f32) -> Self {
+        self.speed = speed.max(0.0);
+        self
+    }
+}
+
+impl AudioBackend for FileBackend {
+    fn list_devices(&self) -> Result<Vec<DeviceInfo>> {
+        let audio = decode_file(&self.path)?;
+        Ok(vec![virtual_device(
+            "File",
+            self.path.display().to_string(),
+            audio.sample_rate,
+        )])
+    }
+
+    fn stream(&self, stop: Arc<AtomicBool>, _devices: &[String]) -> Result<AudioStream> {
+        let audio = decode_file(&self.path)?;
+        tracing::info!(
+            path = %self.path.display(),
+            secs = audio.duration_secs(),
+            speed = self.speed,
+            "Recording from file"
+        );
+        let mut samples = audio.samples.into_iter();
+        replay("file", audio.sample_rate, self.speed, stop, move |frame| {
+            frame
+                .iter_mut()
+                .zip(&mut samples)
+                .map(|(slot, s)| *slot = s)
+                .count()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::wav::encode_wav;
+    use crate::audio::AudioData;
+    use std::sync::atomic::Ordering;
+    use std::time::{Duration, Instant};
+
+    fn ramp_file(len: usize) -> tempfile::NamedTempFile {
+        let audio = AudioData {
+            samples: (0..len).map(|i| (i % 100) as f32 / 200.0).collect(),
+            sample_rate: 8000,
+            channels: 1,
+        };
+        let file = tempfile::Builder::new().suffix(".wav").tempfile().unwrap();
+        std::fs::write(file.path(), encode_wav(&audio).unwrap()).unwrap();
+        file
+    }
+
+    #[test]
+    fn plays_the_whole_file_then_ends() {
+        let file = ramp_file(20_000);
+        let backend = FileBackend::new(file.path()).with_speed(0.0);
+        let audio = backend
+            .record(0.0, Arc::new(AtomicBool::new(false)), &[])
+            .unwrap();
+        assert_eq!(audio.sample_rate, 8000);
+        assert_eq!(audio.samples.len(), 20_000);
+        assert!((audio.samples[99] - 0.495).abs() < 1e-3);
+    }
+
+    #[test]
+    fn honours_the_duration_and_stop_flag() {
+        let file = ramp_file(40_000);
+        let backend = FileBackend::new(file.path()).with_speed(0.0);
+        let audio = backend
+            .record(1.0, Arc::new(AtomicBool::new(false)), &[])
+            .unwrap();
+        assert_eq!(audio.samples.len(), 8000);
+
+        let stop = Arc::new(AtomicBool::new(true));
+        let audio = backend.record(0.0, stop, &[]).unwrap();
+        assert!(audio.samples.len() < 40_000);
+    }
+
+    #[test]
+    fn paces_playback_at_the_given_speed() {
+        let file = ramp_file(8000);
+        let started = Instant::now();
+        let stop = Arc::new(AtomicBool::new(false));
+        FileBackend::new(file.path())
+            .with_speed(4.0)
+            .record(0.0, Arc::clone(&stop), &[])
+            .unwrap();
+        assert!(
+            started.elapsed() >= Duration::from_millis(200),
+            "{:?}",
+            started.elapsed()
+        );
+        assert!(!stop.load(Ordering::Relaxed));
+    }
+}
@@ -0,0 +1,474 @@
+use crate::audio::wav::to_i16;
+use crate::audio::AudioData;
+use crate::errors::{Result, VoxputError};
+
+/// Samples per frame (the reference encoder's default).
+const BLOCK_SIZE: usize = 4096;
+
+/// Highest fixed-predictor order defined by the format.
+const MAX_FIXED_ORDER: usize = 4;
+
+/// Highest residual partition order tried per frame.
+const MAX_PARTITION_ORDER: u32 = 6;
+
+/// Largest Rice parameter expressible with 4-bit parameters (15 is the escape code).
+const MAX_RICE_PARAM: u32 = 14;
+
+/// FLAC limit on the sample rate field in STREAMINFO.
+const MAX_SAMPLE_RATE: u32 = (1 << 20) - 1;
+
+/// Encode `AudioData` as a FLAC stream (16-bit, mono).
+///
+/// Each 4096-sample frame is stored as a constant, fixed-prediction or
+/// verbatim subframe, whichever is smallest; fixed-prediction residuals are
+/// Rice coded with a partition order chosen per frame. This is much simpler
+/// than libFLAC (no LPC, no MD5), but typically halves the size of speech
+/// compared with WAV while staying lossless.
+pub fn encode_flac(audio: &AudioData) -> Result<Vec<u8>> {
+    if audio.sample_rate == 0 || audio.sample_rate > MAX_SAMPLE_RATE {
+        return Err(VoxputError::Audio(format!(
+            "Cannot encode FLAC at {} Hz",
+            audio.sample_rate
+        )));
+    }
+
+    let samples: Vec<i32> = audio.samples.iter().map(|&s| to_i16(s) as i32).collect();
+
+    let mut out = Vec::with_capacity(samples.len() + 42);
+    out.extend_from_slice(b"fLaC");
+    write_streaminfo(&mut out, audio.sample_rate, samples.len() as u64);
+
+    for (number, block) in samples.chunks(BLOCK_SIZE).enumerate() {
+        write_frame(&mut out, number as u64, block);
+    }
+    Ok(out)
+}
+
+fn write_streaminfo(out: &mut Vec<u8>, sample_rate: u32, total_samples: u64) {
+    let mut w = BitWriter::new();
+    // Metadata block header: last block, type 0 (STREAMINFO), 34 bytes.
+    w.write(1, 1);
+    w.write(0, 7);
+    w.write(34, 24);
+
+    let block = BLOCK_SIZE.min(total_samples.max(16) as usize) as u64;
+    w.write(block, 16); // minimum block size
+    w.write(block, 16); // maximum block size
+    w.write(0, 24); // minimum frame size (unknown)
+    w.write(0, 24); // maximum frame size (unknown)
+    w.write(sample_rate as u64, 20);
+    w.write(0, 3); // channels - 1
+    w.write(15, 5); // bits per sample - 1
+    w.write(total_samples, 36);
+    w.write(0, 64); // MD5 signature (unknown)
+    w.write(0, 64);
+    out.extend_from_slice(&w.finish());
+}
+
+fn write_frame(out: &mut Vec<u8>, number: u64, block: &[i32]) {
+    let mut w = BitWriter::new();
+
+    // Frame header.
+    w.write(0b11_1111_1111_1110, 14); // sync code
+    w.write(0, 1); // reserved
+    w.write(0, 1); // fixed block size stream
+    w.write(0b0111, 4); // block size: 16-bit (n - 1) at end of header
+    w.write(0b0000, 4); // sample rate: from STREAMINFO
+    w.write(0b0000, 4); // channels: mono
+    w.write(0b100, 3); // 16 bits per sample
+    w.write(0, 1); // reserved
+    for byte in utf8_number(number) {
+        w.write(byte as u64, 8);
+    }
+    w.write(block.len() as u64 - 1, 16);
+    let crc = crc8(w.bytes());
+    w.write(crc as u64, 8);
+
+    write_subframe(&mut w, block);
+
+    w.align();
+    let crc = crc16(w.bytes());
+    w.write(crc as u64, 16);
+    out.extend_from_slice(&w.finish());
+}
+
+fn write_subframe(w: &mut BitWriter, block: &[i32]) {
+    if block.iter().all(|&s| s == block[0]) {
+        w.write(0, 1);
+        w.write(0b000000, 6); // CONSTANT
+        w.write(0, 1); // no wasted bits
+        w.write_signed(block[0], 16);
+        return;
+    }
+
+    let order = best_fixed_order(block);
+    let residual = fixed_residual(block, order);
+    let (partition_order, params, residual_bits) = best_partitioning(&residual, block.len(), order);
+    let fixed_bits = 8 + order as u64 * 16 + 6 + residual_bits;
+    let verbatim_bits = 8 + block.len() as u64 * 16;
+
+    if verbatim_bits <= fixed_bits {
+        w.write(0, 1);
+        w.write(0b000001, 6); // VERBATIM
+        w.write(0, 1);
+        for &s in block {
+            w.write_signed(s, 16);
+        }
+        return;
+    }
+
+    w.write(0, 1);
+    w.write(0b001000 | order as u64, 6); // FIXED, predictor order in low bits
+    w.write(0, 1);
+    for &s in &block[..order] {
+        w.write_signed(s, 16);
+    }
+
+    w.write(0b00, 2); // Rice coding with 4-bit parameters
+    w.write(partition_order as u64, 4);
+    for (partition, &k) in partitions(&residual, block.len(), order, partition_order).zip(&params) {
+        w.write(k as u64, 4);
+        for &r in partition {
+            w.write_rice(zigzag(r), k);
+        }
+    }
+}
+
+/// Fixed predictor order with the smallest total absolute residual.
+fn best_fixed_order(block: &[i32]) -> usize {
+    (0..=MAX_FIXED_ORDER.min(block.len() - 1))
+        .min_by_key(|&order| {
+            fixed_residual(block, order)
+                .iter()
+                .map(|&r| r.unsigned_abs() as u64)
+                .sum::<u64>()
+        })
+        .unwrap_or(0)
+}
+
+/// Residual of the fixed polynomial predictor of `order`, for the samples
+/// following the `order` warm-up samples.
+fn fixed_residual(block: &[i32], order: usize) -> Vec<i32> {
+    (order..block.len())
+        .map(|i| {
+            let s = |back: usize| block[i - back];
+            match order {
+                0 => s(0),
+                1 => s(0) - s(1),
+                2 => s(0) - 2 * s(1) + s(2),
+                3 => s(0) - 3 * s(1) + 3 * s(2) - s(3),
+                _ => s(0) - 4 * s(1) + 6 * s(2) - 4 * s(3) + s(4),
+            }
+        })
+        .collect()
+}
+
+/// Pick the partition order (and per-partition Rice parameters) giving the
+/// fewest residual bits. Returns `(partition_order, params, bits)`.
+fn best_partitioning(residual: &[i32], block_len: usize, order: usize) -> (u32, Vec<u32>, u64) {
+    let mut best = (0, Vec::new(), u64::MAX);
+    for partition_order in 0..=MAX_PARTITION_ORDER {
+        let count = 1usize << partition_order;
+        // Every partition must hold at least one sample after the warm-up.
+        if !block_len.is_multiple_of(count) || block_len / count <= order {
+            break;
+        }
+        let mut params = Vec::with_capacity(count);
+        let mut bits = 0;
+        for partition in partitions(residual, block_len, order, partition_order) {
+            let (k, partition_bits) = rice_parameter(partition);
+            params.push(k);
+            bits += 4 + partition_bits;
+        }
+        if bits < best.2 {
+            best = (partition_order, params, bits);
+        }
+    }
+    best
+}
+
+/// Split `residual` into the `2^partition_order` partitions of a frame; the
+/// first partition is shorter by the `order` warm-up samples.
+fn partitions(
+    residual: &[i32],
+    block_len: usize,
+    order: usize,
+    partition_order: u32,
+) -> impl Iterator<Item = &[i32]> {
+    let size = block_len >> partition_order;
+    let count = 1usize << partition_order;
+    (0..count).map(move |i| {
+        let start = if i == 0 { 0 } else { i * size - order };
+        &residual[start..(i + 1) * size - order]
+    })
+}
+
+/// Rice parameter for `partition`, estimated from its mean, and the bits it costs.
+fn rice_parameter(partition: &[i32]) -> (u32, u64) {
+    let sum: u64 = partition.iter().map(|&r| zigzag(r) as u64).sum();
+    let mean = sum / partition.len().max(1) as u64;
+    let k = (64 - mean.leading_zeros()).min(MAX_RICE_PARAM);
+    let bits = partition
+        .iter()
+        .map(|&r| (zigzag(r) >> k) as u64 + 1 + k as u64)
+        .sum();
+    (k, bits)
+}
+
+/// Map signed residuals to unsigned: 0, -1, 1, -2, ... → 0, 1, 2, 3, ...
+fn zigzag(r: i32) -> u32 {
+    ((r << 1) ^ (r >> 31)) as u32
+}
+
+/// Frame number in FLAC's extended UTF-8 style coding.
+fn utf8_number(n: u64) -> Vec<u8> {
+    if n < 0x80 {
+        return vec![n as u8];
+    }
+    let len = match n {
+        0..=0x7FF => 2,
+        0x800..=0xFFFF => 3,
+        0x1_0000..=0x1F_FFFF => 4,
+        0x20_0000..=0x3FF_FFFF => 5,
+        0x400_0000..=0x7FFF_FFFF => 6,
+        _ => 7,
+    };
+    let mut bytes = vec![0u8; len];
+    let mut rest = n;
+    for byte in bytes.iter_mut().skip(1).rev() {
+        *byte = 0x80 | (rest & 0x3F) as u8;
+        rest >>= 6;
+    }
+    bytes[0] = ((0xFF00u16 >> len) & 0xFF) as u8 | rest as u8;
+    bytes
+}
+
+fn crc8(data: &[u8]) -> u8 {
+    data.iter().fold(0u8, |mut crc, &byte| {
+        crc ^= byte;
+        for _ in 0..8 {
+            crc = if crc & 0x80 != 0 {
+                (crc << 1) ^ 0x07
+            } else {
+                crc << 1
+            };
+        }
+        crc
+    })
+}
+
+fn crc16(data: &[u8]) -> u16 {
+    data.iter().fold(0u16, |mut crc, &byte| {
+        crc ^= (byte as u16) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x8005
+            } else {
+                crc << 1
+            };
+        }
+        crc
+    })
+}
+
+/// MSB-first bit writer.
+struct BitWriter {
+    bytes: Vec<u8>,
+    acc: u64,
+    bits: u32,
+}
+
+impl BitWriter {
+    fn new() -> Self {
+        Self {
+            bytes: Vec::new(),
+            acc: 0,
+            bits: 0,
+        }
+    }
+
+    /// Write the low `count` bits of `value` (`count` ≤ 32 at a time).
+    fn write(&mut self, value: u64, count: u32) {
+        if count > 32 {
+            self.write(value >> 32, count - 32);
+            self.write(value & 0xFFFF_FFFF, 32);
+            return;
+        }
+        self.acc = (self.acc << count) | (value & ((1u64 << count) - 1));
+        self.bits += count;
+        while self.bits >= 8 {
+            self.bits -= 8;
+            self.bytes.push((self.acc >> self.bits) as u8);
+        }
+    }
+
+    /// Write a two's-complement value in `count` bits.
+    fn write_signed(&mut self, value: i32, count: u32) {
+        self.write(value as u32 as u64, count);
+    }
+
+    /// Rice code `value` with parameter `k`: unary quotient, then `k` low bits.
+    fn write_rice(&mut self, value: u32, k: u32) {
+        let mut quotient = value >> k;
+        while quotient >= 32 {
+            self.write(0, 32);
+            quotient -= 32;
+        }
+        self.write(1, quotient + 1);
+        self.write(value as u64, k);
+    }
+
+    /// Pad with zero bits to the next byte boundary.
+    fn align(&mut self) {
+        if self.bits > 0 {
+            self.write(0, 8 - self.bits);
+        }
+    }
+
+    /// Bytes completed so far.
+    fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        self.align();
+        self.bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::decode::decode_bytes;
+    use crate::audio::wav::encode_wav;
+    use std::f32::consts::TAU;
+
+    fn speechlike(len: usize) -> Vec<f32> {
+        (0..len)
+            .map(|i| {
+                let t = i as f32 / 16000.0;
+                let envelope = if (i / 3000) % 3 == 2 { 0.0 } else { 0.4 };
+                envelope * ((t * 220.0 * TAU).sin() + 0.3 * (t * 1330.0 * TAU).sin())
+            })
+            .collect()
+    }
+
+    fn round_trip(samples: Vec<f32>) {
+        round_trip_at(samples, 16000);
+    }
+
+    /// Encode `samples` at `sample_rate`, decode them with symphonia and
+    /// check every sample survives at 16-bit precision.
+    fn round_trip_at(samples: Vec<f32>, sample_rate: u32) {
+        let audio = AudioData {
+            samples,
+            sample_rate,
+            channels: 1,
+        };
+        let flac = encode_flac(&audio).unwrap();
+        let decoded = decode_bytes(flac).unwrap();
+
+        assert_eq!(decoded.sample_rate, sample_rate);
+        assert_eq!(decoded.samples.len(), audio.samples.len());
+        for (i, (&a, &b)) in audio.samples.iter().zip(&decoded.samples).enumerate() {
+            let expected = to_i16(a) as f32 / 32768.0;
+            assert!((expected - b).abs() < 1e-6, "sample {i}: {expected} vs {b}");
+        }
+    }
+
+    #[test]
+    fn round_trips_through_decoder() {
+        round_trip(speechlike(20_000));
+    }
+
+    #[test]
+    fn round_trips_noise_and_extremes() {
+        let mut samples: Vec<f32> = (0..5000).map(|_| fastrand::f32() * 2.0 - 1.0).collect();
+        samples.extend([1.0, -1.0, 1.0, -1.0, 0.0, 2.0, -2.0]);
+        round_trip(samples);
+    }
+
+    #[test]
+    fn round_trips_tiny_and_silent_input() {
+        round_trip(vec![0.25]);
+        round_trip(vec![0.0; 4096 * 2 + 5]);
+    }
+
+    #[test]
+    fn round_trips_odd_block_sizes() {
+        for len in [2, 15, 16, 17, 255, 257, 1001, BLOCK_SIZE - 1] {
+            round_trip(speechlike(len));
+        }
+    }
+
+    #[test]
+    fn round_trips_a_final_partial_block() {
+        for len in [BLOCK_SIZE + 1, 3 * BLOCK_SIZE + 123] {
+            let noise = (0..len).map(|_| fastrand::f32() - 0.5).collect();
+            round_trip(noise);
+            round_trip(speechlike(len));
+        }
+    }
+
+    #[test]
+    fn round_trips_near_silence_with_zero_rice_parameter() {
+        // A ramp one step per sample: the order-2 residual is all zeros.
+        let ramp: Vec<f32> = (0..BLOCK_SIZE).map(|i| i as f32 / 32768.0).collect();
+        let block: Vec<i32> = ramp.iter().map(|&s| to_i16(s) as i32).collect();
+        let order = best_fixed_order(&block);
+        let residual = fixed_residual(&block, order);
+        let (_, params, _) = best_partitioning(&residual, block.len(), order);
+        assert!(params.iter().all(|&k| k == 0), "{params:?}");
+        round_trip(ramp);
+
+        // Silence with a stray least significant bit now and then.
+        let lsb = 1.0 / 32768.0;
+        let clicks = (0..2 * BLOCK_SIZE + 77)
+            .map(|i| if i % 997 == 0 { lsb } else { 0.0 })
+            .collect();
+        round_trip(clicks);
+    }
+
+    #[test]
+    fn round_trips_full_scale_square_waves() {
+        for half_period in [1, 8, 37] {
+            let square = (0..10_000)
+                .map(|i| [1.0, -1.0][(i / half_period) % 2])
+                .collect();
+            round_trip(square);
+        }
+    }
+
+    #[test]
+    fn round_trips_at_other_sample_rates() {
+        for rate in [8000, 11025, 44100, 48000] {
+            round_trip_at(speechlike(rate as usize / 2), rate);
+        }
+    }
+
+    #[test]
+    fn smaller_than_wav_for_speechlike_audio() {
+        let audio = AudioData {
+            samples: speechlike(160_000),
+            sample_rate: 16000,
+            channels: 1,
+        };
+        let flac = encode_flac(&audio).unwrap().len();
+        let wav = encode_wav(&audio).unwrap().len();
+        assert!(flac * 2 < wav, "flac {flac} bytes vs wav {wav} bytes");
+    }
+
+    #[test]
+    fn frame_numbers_use_utf8_coding() {
+        assert_eq!(utf8_number(0x7F), vec![0x7F]);
+        assert_eq!(utf8_number(0x80), vec![0xC2, 0x80]);
+        assert_eq!(utf8_number(0x800), vec![0xE0, 0xA0, 0x80]);
+    }
+
+    #[test]
+    fn crcs_match_reference_values() {
+        // CRC-8/SMBUS and CRC-16/UMTS check values for "123456789".
+        assert_eq!(crc8(b"123456789"), 0xF4);
+        assert_eq!(crc16(b"123456789"), 0xFEE8);
+    }
+}
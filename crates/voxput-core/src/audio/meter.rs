@@ -0,0 +1,93 @@
+use serde::Serialize;
+
+/// Quietest level reported; digital silence reads as this rather than -inf.
+pub const FLOOR_DB: f32 = -100.0;
+
+/// Bottom of the range [`AudioLevel::fraction`] maps onto `0.0..=1.0`;
+/// quieter input is effectively silence for a level meter.
+const METER_RANGE_DB: f32 = 60.0;
+
+/// Receives the input level about 20 times a second while recording.
+pub type LevelCallback = Box<dyn Fn(AudioLevel) + Send + Sync>;
+
+/// Input level over a short stretch of audio, in dBFS.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct AudioLevel {
+    /// RMS level (full-scale sine ≈ -3 dB).
+    pub rms_db: f32,
+    /// Level of the largest sample.
+    pub peak_db: f32,
+}
+
+impl AudioLevel {
+    /// Measure `samples`; an empty slice reads as silence.
+    pub fn measure(samples: &[f32]) -> Self {
+        if samples.is_empty() {
+            return Self::silence();
+        }
+        let mean_square = samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32;
+        let peak = samples.iter().fold(0.0f32, |m, s| m.max(s.abs()));
+        Self {
+            rms_db: to_db(mean_square.sqrt()),
+            peak_db: to_db(peak),
+        }
+    }
+
+    pub fn silence() -> Self {
+        Self {
+            rms_db: FLOOR_DB,
+            peak_db: FLOOR_DB,
+        }
+    }
+
+    /// Position of `db` on a meter spanning -60..0 dBFS, as `0.0..=1.0`.
+    pub fn fraction(db: f32) -> f32 {
+        ((db + METER_RANGE_DB) / METER_RANGE_DB).clamp(0.0, 1.0)
+    }
+}
+
+fn to_db(amplitude: f32) -> f32 {
+    if amplitude <= 0.0 {
+        return FLOOR_DB;
+    }
+    (20.0 * amplitude.log10()).max(FLOOR_DB)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::TAU;
+
+    #[test]
+    fn full_scale_sine_levels() {
+        let sine: Vec<f32> = (0..1600)
+            .map(|i| (TAU * 440.0 * i as f32 / 16000.0).sin())
+            .collect();
+        let level = AudioLevel::measure(&sine);
+        assert!((level.rms_db + 3.01).abs() < 0.05, "{level:?}");
+        assert!(level.peak_db.abs() < 0.05, "{level:?}");
+    }
+
+    #[test]
+    fn silence_reads_as_floor() {
+        assert_eq!(AudioLevel::measure(&[0.0; 800]), AudioLevel::silence());
+        assert_eq!(AudioLevel::measure(&[]), AudioLevel::silence());
+        assert_eq!(AudioLevel::measure(&[1e-9; 8]).peak_db, FLOOR_DB);
+    }
+
+    #[test]
+    fn peak_tracks_largest_sample() {
+        let level = AudioLevel::measure(&[0.0, -0.5, 0.25]);
+        assert!((level.peak_db + 6.02).abs() < 0.01);
+        assert!(level.rms_db < level.peak_db);
+    }
+
+    #[test]
+    fn fraction_spans_minus_60_to_0_db() {
+        assert_eq!(AudioLevel::fraction(FLOOR_DB), 0.0);
+        assert_eq!(AudioLevel::fraction(-60.0), 0.0);
+        assert_eq!(AudioLevel::fraction(-30.0), 0.5);
+        assert_eq!(AudioLevel::fraction(0.0), 1.0);
+        assert_eq!(AudioLevel::fraction(3.0), 1.0);
+    }
+}
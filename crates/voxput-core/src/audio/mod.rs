@@ -1,8 +1,26 @@
+pub mod chunk;
+pub mod convert;
 pub mod cpal_backend;
+pub mod decode;
+pub mod device;
+pub mod dsp;
+pub mod encode;
+pub mod file;
+pub mod flac;
+pub mod meter;
+pub mod resample;
+pub mod segment;
+pub mod source;
+pub mod stream;
+pub mod synthetic;
+pub mod trim;
+pub mod vad;
+pub mod warm;
 pub mod wav;
 
 use crate::errors::Result;
 use std::sync::{atomic::AtomicBool, Arc};
+use stream::{AudioStream, RecordHooks};
 
 /// Minimum audio duration accepted by transcription providers (seconds).
 pub const MIN_DURATION_SECS: f32 = 0.1;
@@ -43,13 +61,81 @@ mod tests {
         let audio = AudioData { samples: vec![], sample_rate: 16000, channels: 1 };
         assert_eq!(audio.duration_secs(), 0.0);
     }
+
+    #[test]
+    fn configs_display_compactly() {
+        let config = StreamConfigInfo { channels: 2, sample_rate: 48000, sample_format: "f32".into() };
+        assert_eq!(config.to_string(), "2 ch, 48000 Hz, f32");
+
+        let range = |min, max| ConfigRange {
+            channels: 1,
+            min_sample_rate: min,
+            max_sample_rate: max,
+            sample_format: "i16".into(),
+        };
+        assert_eq!(range(8000, 192000).to_string(), "1 ch, 8000–192000 Hz, i16");
+        assert_eq!(range(44100, 44100).to_string(), "1 ch, 44100 Hz, i16");
+    }
 }
 
 /// Information about an audio input device.
 #[derive(Debug, Clone, serde::Serialize)]
 pub struct DeviceInfo {
+    /// Identifier that stays the same across runs: the host and device name,
+    /// e.g. `ALSA:sysdefault:CARD=PCH`.
+    pub id: String,
     pub name: String,
+    /// Audio host (API) the device belongs to, e.g. `ALSA` or `JACK`.
+    pub host: String,
+    /// Whether this is the default input of the default host.
     pub is_default: bool,
+    /// Most input channels the device offers; `audio.channel` counts them
+    /// from 0. Zero if the device could not be queried.
+    pub channels: u16,
+    /// Config the device uses when none is requested; `None` if the device
+    /// could not be queried (for example because it is busy).
+    pub default_config: Option<StreamConfigInfo>,
+    /// Every config range the device accepts for input.
+    pub supported_configs: Vec<ConfigRange>,
+}
+
+/// A single input stream configuration.
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct StreamConfigInfo {
+    pub channels: u16,
+    pub sample_rate: u32,
+    /// Sample format, e.g. `f32` or `i16`.
+    pub sample_format: String,
+}
+
+/// A range of sample rates a device supports for one channel count and format.
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct ConfigRange {
+    pub channels: u16,
+    pub min_sample_rate: u32,
+    pub max_sample_rate: u32,
+    /// Sample format, e.g. `f32` or `i16`.
+    pub sample_format: String,
+}
+
+impl std::fmt::Display for StreamConfigInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ch, {} Hz, {}", self.channels, self.sample_rate, self.sample_format)
+    }
+}
+
+impl std::fmt::Display for ConfigRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.min_sample_rate == self.max_sample_rate {
+            write!(f, "{} ch, {} Hz, {}", self.channels, self.min_sample_rate, self.sample_format)
+        } else {
+            write!(
+                f,
+                "{} ch, {}–{} Hz, {}",
+                self.channels, self.min_sample_rate, self.max_sample_rate, self.sample_format
+            )
+        }
+    }
 }
 
 /// Trait for audio capture backends.
@@ -57,12 +143,23 @@ pub trait AudioBackend: Send + Sync {
     /// List available input devices.
     fn list_devices(&self) -> Result<Vec<DeviceInfo>>;
 
+    /// Start capturing and return a stream of mono frames as they arrive.
+    /// Capture continues until `stop` is set or the stream is dropped.
+    ///
+    /// `devices` is a preference list of device name patterns (see
+    /// [`device::DevicePattern`]); the first available match is used, and
+    /// the system default when none is available or the list is empty.
+    fn stream(&self, stop: Arc<AtomicBool>, devices: &[String]) -> Result<AudioStream>;
+
     /// Record audio until `stop` is set or `duration_secs` elapses, whichever comes first.
     /// A `duration_secs` of `0.0` means no time limit — only the stop flag ends recording.
     fn record(
         &self,
         duration_secs: f32,
         stop: Arc<AtomicBool>,
-        device_name: Option<&str>,
-    ) -> Result<AudioData>;
+        devices: &[String],
+    ) -> Result<AudioData> {
+        self.stream(Arc::clone(&stop), devices)?
+            .record(duration_secs, &stop, RecordHooks::default())
+    }
 }
@@ -0,0 +1,207 @@
+use std::f64::consts::PI;
+
+use crate::audio::AudioData;
+
+/// Zero crossings of the sinc kernel on each side of the centre tap, at
+/// the output rate. More gives a sharper low-pass at a higher cost.
+const ZERO_CROSSINGS: usize = 16;
+
+/// Low-pass cutoff as a fraction of the lower Nyquist frequency; the gap
+/// leaves room for the filter's transition band below Nyquist.
+const ROLLOFF: f64 = 0.92;
+
+/// Most filter phases precomputed; ratios needing more compute taps per sample.
+const MAX_CACHED_PHASES: usize = 4096;
+
+/// Convert `audio` to `target_rate` with a Blackman-windowed sinc filter.
+///
+/// The input is treated as a band-limited signal and re-sampled at the new
+/// rate. When downsampling, the kernel is widened so it also acts as an
+/// anti-aliasing low-pass filter below the new Nyquist frequency. For the
+/// rational ratios between common rates (48 kHz → 16 kHz, 44.1 kHz →
+/// 16 kHz, ...) the filter is a polyphase bank computed once per phase.
+///
+/// Audio already at the target rate is returned unchanged.
+pub fn resample(audio: &AudioData, target_rate: u32) -> AudioData {
+    if audio.sample_rate == target_rate || audio.sample_rate == 0 || target_rate == 0 {
+        return audio.clone();
+    }
+
+    let g = gcd(audio.sample_rate, target_rate);
+    let up = (target_rate / g) as usize;
+    let down = (audio.sample_rate / g) as usize;
+
+    let cutoff = (target_rate as f64 / audio.sample_rate as f64).min(1.0) * ROLLOFF;
+    let half = (ZERO_CROSSINGS as f64 / cutoff).ceil() as usize;
+
+    let bank: Option<Vec<Vec<f32>>> = (up <= MAX_CACHED_PHASES).then(|| {
+        (0..up)
+            .map(|phase| taps(phase as f64 / up as f64, cutoff, half))
+            .collect()
+    });
+
+    let input = &audio.samples;
+    let out_len = (input.len() as u64 * up as u64 / down as u64) as usize;
+    let samples = (0..out_len)
+        .map(|i| {
+            // Output sample `i` sits at input position `base + phase / up`.
+            let pos = i * down;
+            let (base, phase) = (pos / up, pos % up);
+            let computed;
+            let h: &[f32] = match &bank {
+                Some(bank) => &bank[phase],
+                None => {
+                    computed = taps(phase as f64 / up as f64, cutoff, half);
+                    &computed
+                }
+            };
+
+            // Taps cover input samples `base + 1 - half ..= base + half`.
+            let first = base as isize + 1 - half as isize;
+            let mut acc = 0.0;
+            for (k, &tap) in h.iter().enumerate() {
+                let idx = first + k as isize;
+                if idx >= 0 && (idx as usize) < input.len() {
+                    acc += input[idx as usize] * tap;
+                }
+            }
+            acc
+        })
+        .collect();
+
+    AudioData {
+        samples,
+        sample_rate: target_rate,
+        channels: audio.channels,
+    }
+}
+
+/// Filter taps for an output sample `frac` of an input sample past `base`,
+/// normalised to unity gain at DC.
+fn taps(frac: f64, cutoff: f64, half: usize) -> Vec<f32> {
+    let raw: Vec<f64> = (0..2 * half)
+        .map(|k| {
+            // Distance from the output position to input sample `base + 1 - half + k`.
+            let t = frac + half as f64 - 1.0 - k as f64;
+            cutoff * sinc(cutoff * t) * blackman(t / half as f64)
+        })
+        .collect();
+    let sum: f64 = raw.iter().sum();
+    raw.iter().map(|&h| (h / sum) as f32).collect()
+}
+
+fn sinc(x: f64) -> f64 {
+    if x.abs() < 1e-12 {
+        1.0
+    } else {
+        (PI * x).sin() / (PI * x)
+    }
+}
+
+/// Blackman window over `x` in `-1..=1`, zero outside.
+fn blackman(x: f64) -> f64 {
+    if x.abs() >= 1.0 {
+        return 0.0;
+    }
+    0.42 + 0.5 * (PI * x).cos() + 0.08 * (2.0 * PI * x).cos()
+}
+
+fn gcd(mut a: u32, mut b: u32) -> u32 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::TAU;
+
+    fn sine(freq: f64, rate: u32, secs: f64) -> AudioData {
+        let n = (rate as f64 * secs) as usize;
+        let samples = (0..n)
+            .map(|i| (TAU * freq * i as f64 / rate as f64).sin() as f32 * 0.5)
+            .collect();
+        AudioData {
+            samples,
+            sample_rate: rate,
+            channels: 1,
+        }
+    }
+
+    /// Amplitude of the `freq` component (Goertzel), ignoring filter edges.
+    fn amplitude_at(audio: &AudioData, freq: f64) -> f64 {
+        let skip = audio.samples.len() / 10;
+        let body = &audio.samples[skip..audio.samples.len() - skip];
+        let w = TAU * freq / audio.sample_rate as f64;
+        let (mut s1, mut s2) = (0.0, 0.0);
+        for &x in body {
+            let s = x as f64 + 2.0 * w.cos() * s1 - s2;
+            s2 = s1;
+            s1 = s;
+        }
+        let power = s1 * s1 + s2 * s2 - 2.0 * w.cos() * s1 * s2;
+        2.0 * power.sqrt() / body.len() as f64
+    }
+
+    fn rms(audio: &AudioData) -> f64 {
+        let skip = audio.samples.len() / 10;
+        let body = &audio.samples[skip..audio.samples.len() - skip];
+        (body.iter().map(|&x| (x as f64).powi(2)).sum::<f64>() / body.len() as f64).sqrt()
+    }
+
+    #[test]
+    fn same_rate_is_unchanged() {
+        let input = sine(440.0, 16000, 0.1);
+        let out = resample(&input, 16000);
+        assert_eq!(out.samples, input.samples);
+    }
+
+    #[test]
+    fn downsampling_preserves_tone_frequency_and_level() {
+        let out = resample(&sine(1000.0, 48000, 0.5), 16000);
+        assert_eq!(out.sample_rate, 16000);
+        assert_eq!(out.samples.len(), 8000);
+        let level = amplitude_at(&out, 1000.0);
+        assert!((level - 0.5).abs() < 0.01, "1 kHz level {level}");
+        assert!(amplitude_at(&out, 1100.0) < 0.01);
+    }
+
+    #[test]
+    fn upsampling_preserves_tone_frequency_and_level() {
+        let out = resample(&sine(440.0, 16000, 0.5), 48000);
+        assert_eq!(out.samples.len(), 24000);
+        let level = amplitude_at(&out, 440.0);
+        assert!((level - 0.5).abs() < 0.01, "440 Hz level {level}");
+    }
+
+    #[test]
+    fn non_integer_ratio_preserves_tone() {
+        let out = resample(&sine(3000.0, 44100, 0.5), 16000);
+        assert_eq!(out.samples.len(), 8000);
+        let level = amplitude_at(&out, 3000.0);
+        assert!((level - 0.5).abs() < 0.01, "3 kHz level {level}");
+    }
+
+    #[test]
+    fn tones_above_new_nyquist_are_filtered_not_aliased() {
+        // 11 kHz at 48 kHz would alias to 5 kHz at 16 kHz without filtering.
+        let out = resample(&sine(11000.0, 48000, 0.5), 16000);
+        assert!(rms(&out) < 0.005, "residual rms {}", rms(&out));
+        assert!(amplitude_at(&out, 5000.0) < 0.005);
+    }
+
+    #[test]
+    fn dc_is_preserved() {
+        let input = AudioData {
+            samples: vec![0.25; 4800],
+            sample_rate: 48000,
+            channels: 1,
+        };
+        let out = resample(&input, 16000);
+        assert!(out.samples[800..1200]
+            .iter()
+            .all(|&x| (x - 0.25).abs() < 1e-4));
+    }
+}
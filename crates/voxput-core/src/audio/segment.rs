@@ -0,0 +1,253 @@
+use crate::audio::chunk::AudioChunk;
+use crate::audio::vad::{is_speech, level_db};
+use crate::audio::AudioData;
+
+/// Length of the frames classified as speech or pause.
+const FRAME_SECS: f32 = 0.03;
+
+/// Speech frames (about 0.1 s) a segment needs to be worth transcribing,
+/// so a trailing click or breath is not uploaded on its own.
+const MIN_SPEECH_FRAMES: usize = 3;
+
+/// Receives each finished segment of a recording while it is still going.
+pub type SegmentCallback = Box<dyn Fn(AudioChunk) + Send + Sync>;
+
+/// Where recordings are cut for incremental transcription.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SegmentOptions {
+    /// Pause after speech that ends a segment, in seconds.
+    pub pause_secs: f32,
+    /// Shortest segment cut at a pause, in seconds; shorter stretches of
+    /// speech are joined with what follows so each request has context.
+    pub min_segment_secs: f32,
+    /// Longest segment, in seconds. Speech running past this is cut at the
+    /// quietest frame in the last third of the segment.
+    pub max_segment_secs: f32,
+    /// Frame level in dBFS above which a frame can count as speech.
+    pub threshold_db: f32,
+}
+
+impl Default for SegmentOptions {
+    fn default() -> Self {
+        Self {
+            pause_secs: 0.6,
+            min_segment_secs: 3.0,
+            max_segment_secs: 30.0,
+            threshold_db: -40.0,
+        }
+    }
+}
+
+/// Splits audio into segments at pauses as it is captured.
+///
+/// Samples are fed in arbitrary slices with [`push`](Self::push); each time a
+/// segment is complete it is handed to the callback as an [`AudioChunk`]
+/// whose `offset_secs` places it within the whole recording. Cuts fall in
+/// the middle of a pause, and segments never overlap.
+pub struct Segmenter<'a> {
+    opts: SegmentOptions,
+    sample_rate: u32,
+    frame_len: usize,
+    on_segment: Box<dyn FnMut(AudioChunk) + Send + 'a>,
+    /// Samples of the segment in progress.
+    pending: Vec<f32>,
+    /// Samples already handed out, before `pending`.
+    emitted: usize,
+    /// Whole frames of `pending` already classified.
+    classified: usize,
+    speech_frames: usize,
+    /// Consecutive non-speech frames ending at `classified`.
+    silent_frames: usize,
+}
+
+impl<'a> Segmenter<'a> {
+    pub fn new(
+        opts: SegmentOptions,
+        sample_rate: u32,
+        on_segment: impl FnMut(AudioChunk) + Send + 'a,
+    ) -> Self {
+        Self {
+            frame_len: ((sample_rate as f32 * FRAME_SECS) as usize).max(1),
+            opts,
+            sample_rate,
+            on_segment: Box::new(on_segment),
+            pending: Vec::new(),
+            emitted: 0,
+            classified: 0,
+            speech_frames: 0,
+            silent_frames: 0,
+        }
+    }
+
+    /// Feed the next captured samples, emitting any segment they complete.
+    pub fn push(&mut self, samples: &[f32]) {
+        self.pending.extend_from_slice(samples);
+        let frames = |secs: f32| (secs.max(0.0) / FRAME_SECS).ceil() as usize;
+        let pause_frames = frames(self.opts.pause_secs).max(1);
+        let min_frames = frames(self.opts.min_segment_secs);
+        let max_frames = frames(self.opts.max_segment_secs).max(pause_frames + 1);
+
+        while (self.classified + 1) * self.frame_len <= self.pending.len() {
+            let start = self.classified * self.frame_len;
+            if is_speech(
+                &self.pending[start..start + self.frame_len],
+                self.opts.threshold_db,
+            ) {
+                self.speech_frames += 1;
+                self.silent_frames = 0;
+            } else {
+                self.silent_frames += 1;
+            }
+            self.classified += 1;
+
+            let has_speech = self.speech_frames >= MIN_SPEECH_FRAMES;
+            if has_speech && self.silent_frames >= pause_frames && self.classified >= min_frames {
+                let cut = self.classified - self.silent_frames / 2;
+                self.cut(cut * self.frame_len);
+            } else if self.classified >= max_frames {
+                let cut = self.quietest_frame(self.classified * 2 / 3, self.classified) + 1;
+                self.cut(cut * self.frame_len);
+            }
+        }
+    }
+
+    /// Emit what is left once the recording has ended, unless it holds no
+    /// speech.
+    pub fn finish(mut self) {
+        if self.speech_frames >= MIN_SPEECH_FRAMES {
+            let len = self.pending.len();
+            self.cut(len);
+        }
+    }
+
+    /// Index of the quietest whole frame in `from..to`.
+    fn quietest_frame(&self, from: usize, to: usize) -> usize {
+        (from..to)
+            .min_by(|&a, &b| {
+                let level = |i: usize| {
+                    level_db(&self.pending[i * self.frame_len..(i + 1) * self.frame_len])
+                };
+                level(a).total_cmp(&level(b))
+            })
+            .unwrap_or(from)
+    }
+
+    /// Hand out `pending[..at]` as a segment and start the next one after it.
+    fn cut(&mut self, at: usize) {
+        let samples: Vec<f32> = self.pending.drain(..at).collect();
+        let offset = self.emitted;
+        self.emitted += samples.len();
+
+        // Re-classify whatever followed the cut as the start of the next segment.
+        let rest = std::mem::take(&mut self.pending);
+        self.classified = 0;
+        self.speech_frames = 0;
+        self.silent_frames = 0;
+
+        if !samples.is_empty() {
+            (self.on_segment)(AudioChunk {
+                audio: AudioData {
+                    samples,
+                    sample_rate: self.sample_rate,
+                    channels: 1,
+                },
+                offset_secs: offset as f64 / self.sample_rate.max(1) as f64,
+                overlap_secs: 0.0,
+            });
+        }
+        self.push(&rest);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::TAU;
+
+    const RATE: u32 = 16000;
+
+    fn tone(secs: f32) -> Vec<f32> {
+        (0..(secs * RATE as f32) as usize)
+            .map(|i| (TAU * 220.0 * i as f32 / RATE as f32).sin() * 0.3)
+            .collect()
+    }
+
+    fn silence(secs: f32) -> Vec<f32> {
+        vec![0.0; (secs * RATE as f32) as usize]
+    }
+
+    /// Feed `samples` in 20 ms slices and return the emitted segments.
+    fn segment(samples: &[f32], opts: SegmentOptions) -> Vec<AudioChunk> {
+        let mut segments = Vec::new();
+        let mut segmenter = Segmenter::new(opts, RATE, |s| segments.push(s));
+        for slice in samples.chunks(320) {
+            segmenter.push(slice);
+        }
+        segmenter.finish();
+        segments
+    }
+
+    #[test]
+    fn cuts_in_the_middle_of_pauses() {
+        let input = [tone(3.5), silence(1.0), tone(3.5), silence(0.2)].concat();
+        let segments = segment(&input, SegmentOptions::default());
+
+        assert_eq!(segments.len(), 2);
+        assert_eq!(segments[0].offset_secs, 0.0);
+        // The 0.6 s pause is detected 0.6 s into the silence and split in half.
+        assert!(
+            (segments[0].audio.duration_secs() - 3.8).abs() < 0.05,
+            "{}",
+            segments[0].audio.duration_secs()
+        );
+        let joined: usize = segments.iter().map(|s| s.audio.samples.len()).sum();
+        assert_eq!(
+            joined,
+            input.len(),
+            "segments cover the recording without overlap"
+        );
+        let second = &segments[1];
+        assert!((second.offset_secs - segments[0].audio.duration_secs() as f64).abs() < 1e-6);
+    }
+
+    #[test]
+    fn short_phrases_are_joined_with_what_follows() {
+        let input = [tone(1.0), silence(1.0), tone(1.0), silence(1.0), tone(2.0)].concat();
+        let segments = segment(&input, SegmentOptions::default());
+        assert_eq!(segments.len(), 2);
+        assert!(segments[0].audio.duration_secs() >= 3.0);
+    }
+
+    #[test]
+    fn long_speech_is_cut_at_the_maximum() {
+        let opts = SegmentOptions {
+            max_segment_secs: 5.0,
+            ..SegmentOptions::default()
+        };
+        let segments = segment(&tone(12.0), opts);
+        assert!(segments.len() >= 3);
+        assert!(segments
+            .iter()
+            .all(|s| s.audio.duration_secs() <= 5.0 + FRAME_SECS));
+    }
+
+    #[test]
+    fn leading_and_trailing_silence_alone_is_not_emitted() {
+        assert!(segment(&silence(5.0), SegmentOptions::default()).is_empty());
+
+        let input = [tone(4.0), silence(3.0)].concat();
+        let segments = segment(&input, SegmentOptions::default());
+        assert_eq!(
+            segments.len(),
+            1,
+            "the silent tail after the cut is dropped"
+        );
+    }
+
+    #[test]
+    fn speech_up_to_the_end_is_emitted() {
+        let segments = segment(&tone(0.5), SegmentOptions::default());
+        assert_eq!(segments.len(), 1);
+        assert_eq!(segments[0].audio.samples.len(), 8000);
+    }
+}
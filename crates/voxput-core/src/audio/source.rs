@@ -0,0 +1,216 @@
+use std::fmt;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use serde::Deserialize;
+
+use crate::audio::file::FileBackend;
+use crate::audio::stream::{self, AudioStream, FRAME_SECS};
+use crate::audio::synthetic::{Signal, SyntheticBackend};
+use crate::audio::{AudioBackend, DeviceInfo, StreamConfigInfo};
+use crate::errors::{Result, VoxputError};
+
+/// Frequency of `tone` when none is given, in Hz.
+const DEFAULT_TONE_HZ: f32 = 440.0;
+
+/// How long a replaying source waits for the reader to make room.
+const ROOM_POLL: Duration = Duration::from_millis(2);
+
+/// Where recordings come from.
+///
+/// Anything but [`Device`](Self::Device) stands in for the microphone, so the
+/// whole record → transcribe → output flow can run without a sound card.
+/// Written as `device`, `file:<path>`, `tone`, `tone:<hz>` or `silence`.
+#[derive(Debug, Clone, PartialEq, Default, Deserialize)]
+#[serde(try_from = "String")]
+pub enum AudioSource {
+    /// An input device (see `audio.device`).
+    #[default]
+    Device,
+    /// An audio file, played back as if it were being spoken.
+    File(PathBuf),
+    /// A sine tone at this frequency in Hz.
+    Tone(f32),
+    /// Digital silence.
+    Silence,
+}
+
+impl AudioSource {
+    /// Backend producing this source's audio, paced at `speed` times real
+    /// time (0 for as fast as it is read), or `None` for an input device.
+    /// Generated signals are produced at `sample_rate`.
+    pub fn backend(&self, sample_rate: u32, speed: f32) -> Option<Arc<dyn AudioBackend>> {
+        let backend: Arc<dyn AudioBackend> = match self {
+            AudioSource::Device => return None,
+            AudioSource::File(path) => Arc::new(FileBackend::new(path.clone()).with_speed(speed)),
+            AudioSource::Tone(hz) => {
+                Arc::new(SyntheticBackend::new(sample_rate, Signal::tone(*hz)).with_speed(speed))
+            }
+            AudioSource::Silence => {
+                Arc::new(SyntheticBackend::new(sample_rate, Signal::Silence).with_speed(speed))
+            }
+        };
+        Some(backend)
+    }
+}
+
+impl FromStr for AudioSource {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let (kind, arg) = match s.split_once(':') {
+            Some((kind, arg)) => (kind, Some(arg)),
+            None => (s, None),
+        };
+        match (kind, arg) {
+            ("device", None) => Ok(AudioSource::Device),
+            ("silence", None) => Ok(AudioSource::Silence),
+            ("tone", None) => Ok(AudioSource::Tone(DEFAULT_TONE_HZ)),
+            ("tone", Some(hz)) => match hz.parse::<f32>() {
+                Ok(hz) if hz > 0.0 => Ok(AudioSource::Tone(hz)),
+                _ => Err(format!("invalid tone frequency {hz:?}: expected a positive number of Hz")),
+            },
+            ("file", Some(path)) if !path.is_empty() => Ok(AudioSource::File(PathBuf::from(path))),
+            _ => Err(format!(
+                "invalid audio source {s:?}: expected \"device\", \"file:<path>\", \"tone\", \"tone:<hz>\" or \"silence\""
+            )),
+        }
+    }
+}
+
+impl TryFrom<String> for AudioSource {
+    type Error = String;
+
+    fn try_from(s: String) -> std::result::Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl fmt::Display for AudioSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AudioSource::Device => f.write_str("device"),
+            AudioSource::File(path) => write!(f, "file:{}", path.display()),
+            AudioSource::Tone(hz) => write!(f, "tone:{hz}"),
+            AudioSource::Silence => f.write_str("silence"),
+        }
+    }
+}
+
+/// The single "device" a non-device source offers: mono at `sample_rate`.
+pub(crate) fn virtual_device(host: &str, name: String, sample_rate: u32) -> DeviceInfo {
+    DeviceInfo {
+        id: format!("{host}:{name}"),
+        host: host.to_string(),
+        is_default: true,
+        channels: 1,
+        default_config: Some(StreamConfigInfo {
+            channels: 1,
+            sample_rate,
+            sample_format: "f32".to_string(),
+        }),
+        supported_configs: Vec::new(),
+        name,
+    }
+}
+
+/// Feed a new [`AudioStream`] at `sample_rate` from `next` on a thread of its
+/// own, `speed` times faster than real time (0 for as fast as it is read),
+/// until `stop` is set, the reader is dropped or `next` runs out.
+///
+/// `next` fills up to a frame of samples and returns how many it wrote; 0
+/// ends the stream. Unlike a device, the feeder waits for the reader rather
+/// than drop samples, so nothing is lost however fast it runs.
+pub(crate) fn replay(
+    name: &str,
+    sample_rate: u32,
+    speed: f32,
+    stop: Arc<AtomicBool>,
+    mut next: impl FnMut(&mut [f32]) -> usize + Send + 'static,
+) -> Result<AudioStream> {
+    let (mut writer, reader) = stream::channel(sample_rate);
+    let frame_len = ((sample_rate as f32 * FRAME_SECS) as usize).max(1);
+    std::thread::Builder::new()
+        .name(format!("voxput-{name}"))
+        .spawn(move || {
+            let handle = writer.handle();
+            let mut frame = vec![0.0; frame_len];
+            let (start, mut fed) = (Instant::now(), 0usize);
+            while !stop.load(Ordering::Relaxed) && handle.is_active() {
+                if writer.room() < frame_len {
+                    std::thread::sleep(ROOM_POLL);
+                    continue;
+                }
+                let len = next(&mut frame);
+                if len == 0 {
+                    break;
+                }
+                writer.push(frame[..len].iter().copied());
+                fed += len;
+                if speed > 0.0 {
+                    let due =
+                        Duration::from_secs_f64(fed as f64 / (sample_rate as f64 * speed as f64));
+                    if let Some(wait) = due.checked_sub(start.elapsed()) {
+                        std::thread::sleep(wait);
+                    }
+                }
+            }
+        })
+        .map_err(|e| VoxputError::Audio(format!("Failed to start {name} source thread: {e}")))?;
+    Ok(reader)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_every_source_form() {
+        assert_eq!("device".parse(), Ok(AudioSource::Device));
+        assert_eq!("silence".parse(), Ok(AudioSource::Silence));
+        assert_eq!("tone".parse(), Ok(AudioSource::Tone(440.0)));
+        assert_eq!("tone:1000".parse(), Ok(AudioSource::Tone(1000.0)));
+        assert_eq!(
+            "file:/tmp/a:b.wav".parse(),
+            Ok(AudioSource::File(PathBuf::from("/tmp/a:b.wav")))
+        );
+        for bad in ["mic", "tone:0", "tone:loud", "file:", "silence:5"] {
+            assert!(bad.parse::<AudioSource>().is_err(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn display_round_trips() {
+        for source in [
+            AudioSource::Device,
+            AudioSource::File(PathBuf::from("take.wav")),
+            AudioSource::Tone(220.0),
+            AudioSource::Silence,
+        ] {
+            assert_eq!(source.to_string().parse(), Ok(source));
+        }
+    }
+
+    #[test]
+    fn replay_waits_for_the_reader_instead_of_dropping() {
+        let mut left = 100_000usize;
+        let stream = replay(
+            "test",
+            1000,
+            0.0,
+            Arc::new(AtomicBool::new(false)),
+            move |frame| {
+                let len = frame.len().min(left);
+                left -= len;
+                len
+            },
+        )
+        .unwrap();
+        std::thread::sleep(Duration::from_millis(50));
+        let total: usize = stream.map(|f| f.unwrap().samples.len()).sum();
+        assert_eq!(total, 100_000, "more than the 2 s buffer, all delivered");
+    }
+}
@@ -0,0 +1,489 @@
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use rtrb::RingBuffer;
+
+use crate::audio::meter::{AudioLevel, LevelCallback};
+use crate::audio::segment::Segmenter;
+use crate::audio::vad::{VadOptions, VoiceActivityDetector};
+use crate::audio::AudioData;
+use crate::errors::{Result, VoxputError};
+
+/// Length of the frames an [`AudioStream`] yields.
+pub const FRAME_SECS: f32 = 0.02;
+
+/// Audio the ring buffer holds before the writer starts dropping samples
+/// because the reader has fallen behind.
+const BUFFER_SECS: f32 = 2.0;
+
+/// How long the reader sleeps when no whole frame is waiting.
+const READ_POLL: Duration = Duration::from_millis(5);
+
+/// Samples per published level: 20 updates a second.
+const LEVEL_INTERVAL_SECS: f32 = 0.05;
+
+/// Told when capture fails part-way through a recording whose audio so far
+/// is kept.
+pub type WarningCallback = Box<dyn Fn(&str) + Send + Sync>;
+
+/// A fixed-size block of mono samples.
+///
+/// Every frame holds `FRAME_SECS` of audio except possibly the last one
+/// of a stream, which holds whatever was left.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AudioFrame {
+    pub samples: Vec<f32>,
+    pub sample_rate: u32,
+}
+
+/// State shared by the two ends of a stream.
+#[derive(Default)]
+struct Shared {
+    /// Set when the reader is dropped, telling the capture side to stop.
+    closed: AtomicBool,
+    /// First error reported by the capture side.
+    error: Mutex<Option<String>>,
+    /// Samples dropped because the ring buffer was full.
+    overruns: AtomicUsize,
+    /// Samples the capture side has delivered, dropped or not.
+    received: AtomicUsize,
+}
+
+/// Create a stream of mono audio at `sample_rate`: the writer half is fed by
+/// a capture callback, the reader half yields [`AudioFrame`]s.
+///
+/// Samples pass through a lock-free single-producer ring buffer, so the
+/// writer never blocks or allocates and is safe to call from a real-time
+/// audio callback. The stream ends once the writer is dropped and the
+/// reader has drained what is left.
+pub fn channel(sample_rate: u32) -> (StreamWriter, AudioStream) {
+    let capacity = ((sample_rate as f32 * BUFFER_SECS) as usize).max(1);
+    let (producer, consumer) = RingBuffer::new(capacity);
+    let shared = Arc::new(Shared::default());
+    let writer = StreamWriter {
+        producer,
+        shared: Arc::clone(&shared),
+    };
+    let reader = AudioStream {
+        consumer,
+        shared,
+        sample_rate,
+        frame_len: ((sample_rate as f32 * FRAME_SECS) as usize).max(1),
+        ended: false,
+    };
+    (writer, reader)
+}
+
+/// Producer half of a stream, owned by the capture callback.
+pub struct StreamWriter {
+    producer: rtrb::Producer<f32>,
+    shared: Arc<Shared>,
+}
+
+impl StreamWriter {
+    /// Append samples, dropping those that do not fit if the reader has
+    /// fallen more than `BUFFER_SECS` behind.
+    pub fn push(&mut self, samples: impl ExactSizeIterator<Item = f32>) {
+        let wanted = samples.len();
+        self.shared.received.fetch_add(wanted, Ordering::Relaxed);
+        let room = self.producer.slots().min(wanted);
+        let written = match self.producer.write_chunk_uninit(room) {
+            Ok(chunk) => chunk.fill_from_iter(samples),
+            Err(_) => 0,
+        };
+        if written < wanted {
+            self.shared
+                .overruns
+                .fetch_add(wanted - written, Ordering::Relaxed);
+        }
+    }
+
+    /// Samples that fit before the reader has to catch up. Sources that are
+    /// not real-time wait for room instead of having samples dropped.
+    pub fn room(&self) -> usize {
+        self.producer.slots()
+    }
+
+    /// A handle the capture side keeps to report errors and notice when the
+    /// reader has gone away.
+    pub fn handle(&self) -> StreamHandle {
+        StreamHandle(Arc::clone(&self.shared))
+    }
+}
+
+/// Control handle for the capture side of a stream.
+#[derive(Clone)]
+pub struct StreamHandle(Arc<Shared>);
+
+impl StreamHandle {
+    /// Report a capture error; the reader yields it after the audio so far.
+    pub fn fail(&self, message: impl Into<String>) {
+        self.0
+            .error
+            .lock()
+            .unwrap()
+            .get_or_insert_with(|| message.into());
+    }
+
+    /// Whether capture should go on: the reader is still open and no error
+    /// has been reported.
+    pub fn is_active(&self) -> bool {
+        !self.0.closed.load(Ordering::Acquire) && self.0.error.lock().unwrap().is_none()
+    }
+
+    /// Samples delivered so far; a count that stops growing means the
+    /// device has stopped producing audio.
+    pub fn received(&self) -> usize {
+        self.0.received.load(Ordering::Relaxed)
+    }
+}
+
+/// Optional processing [`AudioStream::record`] applies as frames arrive.
+#[derive(Default)]
+pub struct RecordHooks<'a> {
+    /// End the recording once the speaker falls silent.
+    pub auto_stop: Option<&'a VadOptions>,
+    /// Receives the input level about 20 times a second.
+    pub on_level: Option<&'a LevelCallback>,
+    /// Fed the recording as it grows, so segments can be transcribed
+    /// before it ends.
+    pub segmenter: Option<Segmenter<'a>>,
+    /// Told when capture fails after some audio has been recorded. Without
+    /// it, such failures are only logged; either way the audio is kept.
+    pub on_warning: Option<&'a WarningCallback>,
+}
+
+/// Consumer half of a stream, yielding fixed-size frames as they arrive.
+///
+/// Iterating blocks until a whole frame is available. After the writer is
+/// dropped, the remaining samples are yielded (the last frame may be short),
+/// then a capture error if one was reported, then `None`.
+pub struct AudioStream {
+    consumer: rtrb::Consumer<f32>,
+    shared: Arc<Shared>,
+    sample_rate: u32,
+    frame_len: usize,
+    ended: bool,
+}
+
+impl AudioStream {
+    /// Rate of the samples in every frame.
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    /// Read frames until the stream ends, returning them as one recording.
+    ///
+    /// Setting `stop` asks the capture side to finish; the frames already
+    /// captured are still collected. `stop` is also set here when
+    /// `duration_secs` (if positive) of audio has been read, or when
+    /// `hooks.auto_stop` hears trailing silence after speech.
+    ///
+    /// A capture error (such as the device being unplugged) fails the
+    /// recording only if nothing was captured before it; otherwise the audio
+    /// so far is returned and the error reported to `hooks.on_warning`.
+    pub fn record(
+        self,
+        duration_secs: f32,
+        stop: &AtomicBool,
+        hooks: RecordHooks<'_>,
+    ) -> Result<AudioData> {
+        let RecordHooks {
+            auto_stop,
+            on_level,
+            mut segmenter,
+            on_warning,
+        } = hooks;
+        let sample_rate = self.sample_rate;
+        let limit = (duration_secs > 0.0).then_some((duration_secs * sample_rate as f32) as usize);
+        let level_len = ((sample_rate as f32 * LEVEL_INTERVAL_SECS) as usize).max(1);
+        let mut vad = auto_stop.map(|opts| VoiceActivityDetector::new(opts.clone(), sample_rate));
+        let mut samples = Vec::new();
+        let mut metered = 0;
+
+        for frame in self {
+            let mut frame = match frame {
+                Ok(frame) => frame,
+                Err(e) if !samples.is_empty() => {
+                    let secs = samples.len() as f32 / sample_rate as f32;
+                    let warning = format!(
+                        "Recording cut short ({e}); keeping the {secs:.1}s captured before it"
+                    );
+                    tracing::warn!("{warning}");
+                    if let Some(on_warning) = on_warning {
+                        on_warning(&warning);
+                    }
+                    stop.store(true, Ordering::Relaxed);
+                    break;
+                }
+                Err(e) => return Err(e),
+            };
+            if let Some(limit) = limit {
+                frame.samples.truncate(limit - samples.len());
+            }
+            samples.extend_from_slice(&frame.samples);
+            if let Some(segmenter) = segmenter.as_mut() {
+                segmenter.push(&frame.samples);
+            }
+
+            if let Some(on_level) = on_level {
+                while samples.len() - metered >= level_len {
+                    on_level(AudioLevel::measure(&samples[metered..metered + level_len]));
+                    metered += level_len;
+                }
+            }
+            if let Some(vad) = vad.as_mut() {
+                vad.push(&frame.samples);
+                if vad.should_stop() && !stop.swap(true, Ordering::Relaxed) {
+                    tracing::debug!("Trailing silence detected; stopping");
+                }
+            }
+            if let Some(limit) = limit {
+                if samples.len() >= limit {
+                    stop.store(true, Ordering::Relaxed); // tell listener to exit too
+                    break;
+                }
+            }
+        }
+
+        if let Some(segmenter) = segmenter {
+            segmenter.finish();
+        }
+        Ok(AudioData {
+            samples,
+            sample_rate,
+            channels: 1,
+        })
+    }
+
+    fn take(&mut self, n: usize) -> Vec<f32> {
+        match self.consumer.read_chunk(n) {
+            Ok(chunk) => chunk.into_iter().collect(),
+            Err(_) => Vec::new(),
+        }
+    }
+
+    fn frame(&self, samples: Vec<f32>) -> AudioFrame {
+        AudioFrame {
+            samples,
+            sample_rate: self.sample_rate,
+        }
+    }
+}
+
+impl Iterator for AudioStream {
+    type Item = Result<AudioFrame>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.ended {
+            return None;
+        }
+        loop {
+            if self.consumer.slots() >= self.frame_len {
+                let samples = self.take(self.frame_len);
+                return Some(Ok(self.frame(samples)));
+            }
+            if self.consumer.is_abandoned() {
+                // The writer is gone, so nothing more will arrive.
+                let rest = self.consumer.slots();
+                if rest > 0 {
+                    let samples = self.take(rest);
+                    return Some(Ok(self.frame(samples)));
+                }
+                self.ended = true;
+                let overruns = self.shared.overruns.load(Ordering::Relaxed);
+                if overruns > 0 {
+                    tracing::warn!(
+                        samples = overruns,
+                        "Audio reader fell behind; samples were dropped"
+                    );
+                }
+                let error = self.shared.error.lock().unwrap().take();
+                return error.map(|e| {
+                    Err(VoxputError::Audio(format!(
+                        "Stream error during recording: {e}"
+                    )))
+                });
+            }
+            std::thread::sleep(READ_POLL);
+        }
+    }
+}
+
+impl Drop for AudioStream {
+    fn drop(&mut self) {
+        self.shared.closed.store(true, Ordering::Release);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::segment::SegmentOptions;
+    use std::sync::atomic::AtomicUsize;
+    use std::thread;
+
+    const RATE: u32 = 16000;
+
+    /// Feed `samples` from another thread in uneven bursts, as a device would.
+    fn feed(samples: Vec<f32>) -> AudioStream {
+        let (mut writer, reader) = channel(RATE);
+        thread::spawn(move || {
+            for burst in samples.chunks(437) {
+                writer.push(burst.iter().copied());
+                thread::sleep(Duration::from_micros(200));
+            }
+        });
+        reader
+    }
+
+    #[test]
+    fn yields_fixed_size_frames_then_the_rest() {
+        let input: Vec<f32> = (0..1000).map(|i| i as f32).collect();
+        let frames: Vec<AudioFrame> = feed(input.clone()).map(|f| f.unwrap()).collect();
+        let lens: Vec<usize> = frames.iter().map(|f| f.samples.len()).collect();
+        assert_eq!(lens, vec![320, 320, 320, 40]);
+        assert!(frames.iter().all(|f| f.sample_rate == RATE));
+        let joined: Vec<f32> = frames
+            .iter()
+            .flat_map(|f| f.samples.iter().copied())
+            .collect();
+        assert_eq!(joined, input);
+    }
+
+    #[test]
+    fn capture_error_is_reported_after_the_audio() {
+        let (mut writer, reader) = channel(RATE);
+        writer.push([0.5; 320].into_iter());
+        writer.handle().fail("device unplugged");
+        assert!(!writer.handle().is_active());
+        drop(writer);
+
+        let items: Vec<_> = reader.collect();
+        assert_eq!(items.len(), 2);
+        assert!(items[0].is_ok());
+        let err = items[1].as_ref().unwrap_err().to_string();
+        assert!(err.contains("device unplugged"), "{err}");
+    }
+
+    #[test]
+    fn capture_error_after_audio_keeps_the_recording() {
+        let (mut writer, reader) = channel(RATE);
+        writer.push([0.5; 800].into_iter());
+        writer.handle().fail("device unplugged");
+        drop(writer);
+
+        let warnings = Arc::new(Mutex::new(Vec::new()));
+        let seen = Arc::clone(&warnings);
+        let on_warning: WarningCallback =
+            Box::new(move |w| seen.lock().unwrap().push(w.to_string()));
+        let hooks = RecordHooks {
+            on_warning: Some(&on_warning),
+            ..RecordHooks::default()
+        };
+        let stop = AtomicBool::new(false);
+        let audio = reader.record(0.0, &stop, hooks).unwrap();
+        assert_eq!(audio.samples.len(), 800);
+        assert!(stop.load(Ordering::Relaxed));
+        let warnings = warnings.lock().unwrap();
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("device unplugged"), "{}", warnings[0]);
+    }
+
+    #[test]
+    fn capture_error_before_any_audio_fails_the_recording() {
+        let (writer, reader) = channel(RATE);
+        writer.handle().fail("device unplugged");
+        drop(writer);
+        let stop = AtomicBool::new(false);
+        assert!(reader.record(0.0, &stop, RecordHooks::default()).is_err());
+    }
+
+    #[test]
+    fn dropping_the_reader_deactivates_the_handle() {
+        let (writer, reader) = channel(RATE);
+        let handle = writer.handle();
+        assert!(handle.is_active());
+        drop(reader);
+        assert!(!handle.is_active());
+    }
+
+    #[test]
+    fn writer_drops_samples_when_the_buffer_is_full() {
+        // Two seconds at 50 Hz is 100 samples.
+        let (mut writer, reader) = channel(50);
+        writer.push(vec![0.1; 150].into_iter());
+        assert_eq!(writer.shared.overruns.load(Ordering::Relaxed), 50);
+        drop(writer);
+        let total: usize = reader.map(|f| f.unwrap().samples.len()).sum();
+        assert_eq!(total, 100);
+    }
+
+    #[test]
+    fn record_stops_at_duration_and_sets_the_flag() {
+        let stop = AtomicBool::new(false);
+        let audio = feed(vec![0.0; 16000])
+            .record(0.5, &stop, RecordHooks::default())
+            .unwrap();
+        assert_eq!(audio.samples.len(), 8000);
+        assert!(stop.load(Ordering::Relaxed));
+    }
+
+    #[test]
+    fn record_reads_everything_without_a_limit() {
+        let stop = AtomicBool::new(false);
+        let audio = feed(vec![0.0; 5000])
+            .record(0.0, &stop, RecordHooks::default())
+            .unwrap();
+        assert_eq!(audio.samples.len(), 5000);
+        assert_eq!(audio.sample_rate, RATE);
+        assert!(!stop.load(Ordering::Relaxed));
+    }
+
+    #[test]
+    fn record_sets_stop_on_trailing_silence() {
+        let tone =
+            (0..16000).map(|i| (std::f32::consts::TAU * 220.0 * i as f32 / 16000.0).sin() * 0.3);
+        let input: Vec<f32> = tone.chain(std::iter::repeat_n(0.0, 32000)).collect();
+        let stop = AtomicBool::new(false);
+        let hooks = RecordHooks {
+            auto_stop: Some(&VadOptions::default()),
+            ..RecordHooks::default()
+        };
+        feed(input).record(0.0, &stop, hooks).unwrap();
+        assert!(stop.load(Ordering::Relaxed));
+    }
+
+    #[test]
+    fn record_feeds_the_segmenter_up_to_the_limit() {
+        let mut fed = Vec::new();
+        let segmenter = Segmenter::new(SegmentOptions::default(), RATE, |s| fed.push(s));
+        let tone =
+            (0..16000).map(|i| (std::f32::consts::TAU * 220.0 * i as f32 / 16000.0).sin() * 0.3);
+        let stop = AtomicBool::new(false);
+        let hooks = RecordHooks {
+            segmenter: Some(segmenter),
+            ..RecordHooks::default()
+        };
+        let audio = feed(tone.collect()).record(0.5, &stop, hooks).unwrap();
+        assert_eq!(fed.len(), 1);
+        assert_eq!(fed[0].audio.samples, audio.samples);
+    }
+
+    #[test]
+    fn record_publishes_levels_at_20_hz() {
+        let count = Arc::new(AtomicUsize::new(0));
+        let seen = Arc::clone(&count);
+        let on_level: LevelCallback = Box::new(move |_| {
+            seen.fetch_add(1, Ordering::Relaxed);
+        });
+        let stop = AtomicBool::new(false);
+        let hooks = RecordHooks {
+            on_level: Some(&on_level),
+            ..RecordHooks::default()
+        };
+        feed(vec![0.1; 16000]).record(0.0, &stop, hooks).unwrap();
+        assert_eq!(count.load(Ordering::Relaxed), 20);
+    }
+}
@@ -0,0 +1,160 @@
+use std::f32::consts::TAU;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
+use crate::audio::source::{replay, virtual_device};
+use crate::audio::stream::AudioStream;
+use crate::audio::{AudioBackend, DeviceInfo};
+use crate::errors::Result;
+
+/// Amplitude of [`Signal::tone`]: about -13 dBFS RMS, well above the
+/// default speech threshold.
+const TONE_AMPLITUDE: f32 = 0.3;
+
+/// A generated signal.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Signal {
+    /// A sine wave.
+    Tone { freq_hz: f32, amplitude: f32 },
+    /// Digital silence.
+    Silence,
+}
+
+impl Signal {
+    /// A sine wave at `freq_hz` loud enough to count as speech.
+    pub fn tone(freq_hz: f32) -> Self {
+        Signal::Tone {
+            freq_hz,
+            amplitude: TONE_AMPLITUDE,
+        }
+    }
+
+    fn sample(self, index: usize, sample_rate: u32) -> f32 {
+        match self {
+            Signal::Tone { freq_hz, amplitude } => {
+                // Reduce the phase to one cycle first to keep f32 precise.
+                let cycle = (sample_rate as f64 / freq_hz as f64).max(1.0);
+                let phase = (index as f64 % cycle) / cycle;
+                (TAU * phase as f32).sin() * amplitude
+            }
+            Signal::Silence => 0.0,
+        }
+    }
+}
+
+/// Generates tones and silence in place of a microphone.
+///
+/// A backend made with [`new`](Self::new) produces its signal until the
+/// recording is stopped; one made with [`sequence`](Self::sequence) plays
+/// each part for its length and then ends. Audio is paced at `speed` times
+/// real time (0 for as fast as it is read). `devices` is ignored.
+pub struct SyntheticBackend {
+    sample_rate: u32,
+    /// Signals in order with their length in seconds; `None` never ends.
+    parts: Vec<(Signal, Option<f32>)>,
+    speed: f32,
+}
+
+impl SyntheticBackend {
+    /// Produce `signal` at `sample_rate` until stopped.
+    pub fn new(sample_rate: u32, signal: Signal) -> Self {
+        Self {
+            sample_rate,
+            parts: vec![(signal, None)],
+            speed: 1.0,
+        }
+    }
+
+    /// Produce each signal for its length in seconds, then end.
+    pub fn sequence(sample_rate: u32, parts: Vec<(Signal, f32)>) -> Self {
+        Self {
+            sample_rate,
+            parts: parts
+                .into_iter()
+                .map(|(signal, secs)| (signal, Some(secs)))
+                .collect(),
+            speed: 1.0,
+        }
+    }
+
+    /// Produce audio `speed` times faster than real time; 0 removes the pacing.
+    pub fn with_speed(mut self, speed: f32) -> Self {
+        self.speed = speed.max(0.0);
+        self
+    }
+}
+
+impl AudioBackend for SyntheticBackend {
+    fn list_devices(&self) -> Result<Vec<DeviceInfo>> {
+        Ok(vec![virtual_device(
+            "Synthetic",
+            "generator".to_string(),
+            self.sample_rate,
+        )])
+    }
+
+    fn stream(&self, stop: Arc<AtomicBool>, _devices: &[String]) -> Result<AudioStream> {
+        let rate = self.sample_rate;
+        let mut parts = self
+            .parts
+            .iter()
+            .map(|&(signal, secs)| (signal, secs.map(|s| (s.max(0.0) * rate as f32) as usize)))
+            .collect::<Vec<_>>()
+            .into_iter();
+        let (mut current, mut produced) = (parts.next(), 0usize);
+        let mut index = 0usize;
+
+        replay("synthetic", rate, self.speed, stop, move |frame| {
+            let mut len = 0;
+            while len < frame.len() {
+                let Some((signal, part_len)) = current else {
+                    break;
+                };
+                if part_len.is_some_and(|n| produced >= n) {
+                    (current, produced) = (parts.next(), 0);
+                    continue;
+                }
+                frame[len] = signal.sample(index, rate);
+                (len, produced, index) = (len + 1, produced + 1, index + 1);
+            }
+            len
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::meter::AudioLevel;
+
+    #[test]
+    fn sequence_plays_each_part_then_ends() {
+        let backend = SyntheticBackend::sequence(
+            16000,
+            vec![(Signal::tone(440.0), 0.5), (Signal::Silence, 0.25)],
+        )
+        .with_speed(0.0);
+        let audio = backend
+            .record(0.0, Arc::new(AtomicBool::new(false)), &[])
+            .unwrap();
+
+        assert_eq!(audio.samples.len(), 12000);
+        let tone = AudioLevel::measure(&audio.samples[..8000]);
+        assert!(
+            (tone.peak_db - 20.0 * TONE_AMPLITUDE.log10()).abs() < 0.1,
+            "{tone:?}"
+        );
+        assert!(audio.samples[8000..].iter().all(|&s| s == 0.0));
+    }
+
+    #[test]
+    fn endless_signal_runs_until_the_duration() {
+        let backend = SyntheticBackend::new(8000, Signal::tone(100.0)).with_speed(0.0);
+        let audio = backend
+            .record(3.0, Arc::new(AtomicBool::new(false)), &[])
+            .unwrap();
+        assert_eq!(audio.samples.len(), 24000);
+        // 100 Hz at 8 kHz repeats every 80 samples.
+        assert!((audio.samples[20] - audio.samples[23_940]).abs() < 1e-4);
+    }
+}
@@ -0,0 +1,216 @@
+use crate::audio::vad::level_db;
+use crate::audio::AudioData;
+use crate::errors::{Result, VoxputError};
+
+/// Length of the frames whose levels are compared with the threshold.
+const FRAME_SECS: f32 = 0.02;
+
+/// Shortest run of loud frames treated as speech; shorter bursts such as a
+/// key click are trimmed with the silence around them.
+const MIN_SPEECH_SECS: f32 = 0.1;
+
+/// How far above the noise floor a frame must be to count as speech.
+const MARGIN_DB: f32 = 12.0;
+
+/// Lowest speech threshold, so near-digital silence does not turn hiss
+/// into speech.
+const MIN_THRESHOLD_DB: f32 = -60.0;
+
+/// Fraction of frames assumed to be background when estimating the floor.
+const NOISE_PERCENTILE: f32 = 0.1;
+
+/// Settings for [`trim_silence`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrimOptions {
+    /// Level in dBFS that always counts as speech; the adaptive threshold
+    /// never rises above it, even in a noisy room.
+    pub threshold_db: f32,
+    /// Audio kept on each side of the speech so onsets and tails survive, in seconds.
+    pub padding_secs: f32,
+}
+
+impl Default for TrimOptions {
+    fn default() -> Self {
+        Self {
+            threshold_db: -40.0,
+            padding_secs: 0.2,
+        }
+    }
+}
+
+/// Remove leading and trailing silence from `audio`.
+///
+/// The speech threshold adapts to the recording: it sits `MARGIN_DB` above
+/// the noise floor (the level of the quietest tenth of the frames), clamped
+/// between `MIN_THRESHOLD_DB` and `opts.threshold_db`. Everything before the
+/// first and after the last run of at least `MIN_SPEECH_SECS` above the
+/// threshold is dropped, apart from `opts.padding_secs` on each side.
+///
+/// Returns [`VoxputError::NoSpeech`] when no such run exists.
+pub fn trim_silence(audio: &AudioData, opts: &TrimOptions) -> Result<AudioData> {
+    let Some((speech_start, speech_end)) = speech_span(audio, opts) else {
+        return Err(VoxputError::NoSpeech);
+    };
+
+    let pad = (opts.padding_secs.max(0.0) * audio.sample_rate as f32) as usize;
+    let start = speech_start.saturating_sub(pad);
+    let end = (speech_end + pad).min(audio.samples.len());
+    tracing::debug!(
+        leading_secs = start as f32 / audio.sample_rate as f32,
+        trailing_secs = (audio.samples.len() - end) as f32 / audio.sample_rate as f32,
+        "Trimmed silence"
+    );
+
+    Ok(AudioData {
+        samples: audio.samples[start..end].to_vec(),
+        sample_rate: audio.sample_rate,
+        channels: audio.channels,
+    })
+}
+
+/// Whether `audio` holds any speech by the measure [`trim_silence`] uses.
+pub fn contains_speech(audio: &AudioData, opts: &TrimOptions) -> bool {
+    speech_span(audio, opts).is_some()
+}
+
+/// Sample range from the start of the first to the end of the last run of speech.
+fn speech_span(audio: &AudioData, opts: &TrimOptions) -> Option<(usize, usize)> {
+    let frame_len = ((audio.sample_rate as f32 * FRAME_SECS) as usize).max(1);
+    let levels: Vec<f32> = audio.samples.chunks(frame_len).map(level_db).collect();
+    let threshold =
+        (noise_floor_db(&levels) + MARGIN_DB).clamp(MIN_THRESHOLD_DB, opts.threshold_db);
+
+    let min_run = (MIN_SPEECH_SECS / FRAME_SECS).ceil() as usize;
+    let mut run = 0;
+    let mut speech: Option<(usize, usize)> = None;
+    for (i, &level) in levels.iter().enumerate() {
+        run = if level >= threshold { run + 1 } else { 0 };
+        if run >= min_run {
+            let first = speech.map_or(i + 1 - min_run, |(first, _)| first);
+            speech = Some((first, i));
+        }
+    }
+    tracing::trace!(
+        threshold_db = threshold,
+        found = speech.is_some(),
+        "Speech threshold"
+    );
+    speech.map(|(first, last)| {
+        (
+            first * frame_len,
+            ((last + 1) * frame_len).min(audio.samples.len()),
+        )
+    })
+}
+
+/// Estimated background level: the `NOISE_PERCENTILE` quantile of `levels`.
+fn noise_floor_db(levels: &[f32]) -> f32 {
+    if levels.is_empty() {
+        return f32::NEG_INFINITY;
+    }
+    let mut sorted = levels.to_vec();
+    sorted.sort_by(f32::total_cmp);
+    sorted[((sorted.len() - 1) as f32 * NOISE_PERCENTILE) as usize]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::TAU;
+
+    const RATE: u32 = 16000;
+
+    fn tone(secs: f32, amplitude: f32) -> Vec<f32> {
+        (0..(secs * RATE as f32) as usize)
+            .map(|i| (TAU * 220.0 * i as f32 / RATE as f32).sin() * amplitude)
+            .collect()
+    }
+
+    fn noise(secs: f32, amplitude: f32) -> Vec<f32> {
+        (0..(secs * RATE as f32) as usize)
+            .map(|_| (fastrand::f32() * 2.0 - 1.0) * amplitude)
+            .collect()
+    }
+
+    fn audio(parts: &[Vec<f32>]) -> AudioData {
+        AudioData {
+            samples: parts.concat(),
+            sample_rate: RATE,
+            channels: 1,
+        }
+    }
+
+    #[test]
+    fn trims_silence_at_both_ends_keeping_padding() {
+        let input = audio(&[vec![0.0; 16000], tone(1.0, 0.3), vec![0.0; 16000]]);
+        let out = trim_silence(&input, &TrimOptions::default()).unwrap();
+        // 1 s of speech plus 0.2 s of padding on each side.
+        assert!(
+            (out.duration_secs() - 1.4).abs() < 0.03,
+            "{}",
+            out.duration_secs()
+        );
+        assert_eq!(out.sample_rate, RATE);
+    }
+
+    #[test]
+    fn key_click_before_speech_is_trimmed() {
+        let input = audio(&[
+            tone(0.03, 0.9),
+            vec![0.0; 16000],
+            tone(1.0, 0.3),
+            vec![0.0; 8000],
+        ]);
+        let out = trim_silence(&input, &TrimOptions::default()).unwrap();
+        assert!(out.duration_secs() < 1.45, "{}", out.duration_secs());
+        assert!(
+            out.samples[..800].iter().all(|&s| s == 0.0),
+            "starts in padding"
+        );
+    }
+
+    #[test]
+    fn continuous_speech_is_unchanged() {
+        let input = audio(&[tone(2.0, 0.3)]);
+        let out = trim_silence(&input, &TrimOptions::default()).unwrap();
+        assert_eq!(out.samples.len(), input.samples.len());
+    }
+
+    #[test]
+    fn threshold_adapts_to_background_noise() {
+        // -45 dBFS hiss would count as speech against a fixed -60 dB floor.
+        let input = audio(&[noise(1.0, 0.01), tone(1.0, 0.3), noise(1.0, 0.01)]);
+        let out = trim_silence(&input, &TrimOptions::default()).unwrap();
+        assert!(
+            (out.duration_secs() - 1.4).abs() < 0.03,
+            "{}",
+            out.duration_secs()
+        );
+    }
+
+    #[test]
+    fn quiet_speech_in_a_quiet_room_is_kept() {
+        // -50 dBFS speech is below the -40 dB cap but well above the floor.
+        let input = audio(&[noise(1.0, 0.0001), tone(1.0, 0.0045), noise(1.0, 0.0001)]);
+        let out = trim_silence(&input, &TrimOptions::default()).unwrap();
+        assert!(
+            (out.duration_secs() - 1.4).abs() < 0.03,
+            "{}",
+            out.duration_secs()
+        );
+    }
+
+    #[test]
+    fn recording_without_speech_is_rejected() {
+        for input in [
+            audio(&[vec![0.0; 32000]]),
+            audio(&[noise(2.0, 0.002)]),
+            audio(&[vec![0.0; 8000], tone(0.04, 0.9), vec![0.0; 8000]]),
+            audio(&[]),
+        ] {
+            let err = trim_silence(&input, &TrimOptions::default()).unwrap_err();
+            assert!(matches!(err, VoxputError::NoSpeech), "{err}");
+            assert!(!contains_speech(&input, &TrimOptions::default()));
+        }
+    }
+}
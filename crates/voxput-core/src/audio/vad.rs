@@ -0,0 +1,211 @@
+/// Length of the frames the detector classifies.
+const FRAME_SECS: f32 = 0.03;
+
+/// Zero-crossing rate (crossings per sample) above which a frame is treated
+/// as noise even when loud: voiced speech sits well below it, broadband
+/// hiss and fan noise around 0.5.
+const MAX_SPEECH_ZCR: f32 = 0.4;
+
+/// Settings for ending a recording automatically once the speaker stops.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VadOptions {
+    /// Silence after speech that ends the recording, in seconds.
+    pub trailing_silence_secs: f32,
+    /// Frame level in dBFS above which a frame can count as speech.
+    pub threshold_db: f32,
+    /// Speech needed before silence can end the recording, in seconds, so
+    /// a cough or click does not end it straight away.
+    pub min_speech_secs: f32,
+}
+
+impl Default for VadOptions {
+    fn default() -> Self {
+        Self {
+            trailing_silence_secs: 1.5,
+            threshold_db: -40.0,
+            min_speech_secs: 0.3,
+        }
+    }
+}
+
+/// Frame-based voice activity detector using short-term energy and
+/// zero-crossing rate.
+///
+/// Audio is fed in arbitrary slices with [`push`](Self::push); once at least
+/// `min_speech_secs` of speech has been heard, [`should_stop`](Self::should_stop)
+/// turns true after `trailing_silence_secs` of continuous non-speech.
+#[derive(Debug, Clone)]
+pub struct VoiceActivityDetector {
+    opts: VadOptions,
+    frame_len: usize,
+    /// Samples not yet forming a whole frame.
+    pending: Vec<f32>,
+    speech_frames: usize,
+    /// Consecutive non-speech frames since the last speech frame.
+    silent_frames: usize,
+    min_speech_frames: usize,
+    stop_after_frames: usize,
+}
+
+impl VoiceActivityDetector {
+    pub fn new(opts: VadOptions, sample_rate: u32) -> Self {
+        let frame_len = ((sample_rate as f32 * FRAME_SECS) as usize).max(1);
+        let frames = |secs: f32| (secs.max(0.0) / FRAME_SECS).ceil() as usize;
+        Self {
+            min_speech_frames: frames(opts.min_speech_secs).max(1),
+            stop_after_frames: frames(opts.trailing_silence_secs).max(1),
+            opts,
+            frame_len,
+            pending: Vec::with_capacity(frame_len),
+            speech_frames: 0,
+            silent_frames: 0,
+        }
+    }
+
+    /// Feed the next captured samples.
+    pub fn push(&mut self, samples: &[f32]) {
+        self.pending.extend_from_slice(samples);
+        let whole = self.pending.len() / self.frame_len * self.frame_len;
+        for frame in self.pending[..whole].chunks_exact(self.frame_len) {
+            if is_speech(frame, self.opts.threshold_db) {
+                self.speech_frames += 1;
+                self.silent_frames = 0;
+            } else {
+                self.silent_frames += 1;
+            }
+        }
+        self.pending.drain(..whole);
+    }
+
+    /// Whether enough speech has been heard for silence to end the recording.
+    pub fn speech_detected(&self) -> bool {
+        self.speech_frames >= self.min_speech_frames
+    }
+
+    /// Whether the speaker has been silent long enough after speaking.
+    pub fn should_stop(&self) -> bool {
+        self.speech_detected() && self.silent_frames >= self.stop_after_frames
+    }
+}
+
+/// Whether `frame` looks like speech: loud enough, and not noise-like.
+pub fn is_speech(frame: &[f32], threshold_db: f32) -> bool {
+    level_db(frame) >= threshold_db && zero_crossing_rate(frame) <= MAX_SPEECH_ZCR
+}
+
+/// RMS level of `frame` in dBFS (full-scale sine ≈ -3 dB, silence = -inf).
+pub fn level_db(frame: &[f32]) -> f32 {
+    if frame.is_empty() {
+        return f32::NEG_INFINITY;
+    }
+    let mean_square = frame.iter().map(|s| s * s).sum::<f32>() / frame.len() as f32;
+    10.0 * mean_square.log10()
+}
+
+/// Fraction of adjacent sample pairs whose signs differ.
+pub fn zero_crossing_rate(frame: &[f32]) -> f32 {
+    if frame.len() < 2 {
+        return 0.0;
+    }
+    let crossings = frame
+        .windows(2)
+        .filter(|w| (w[0] >= 0.0) != (w[1] >= 0.0))
+        .count();
+    crossings as f32 / (frame.len() - 1) as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::TAU;
+
+    const RATE: u32 = 16000;
+
+    fn tone(secs: f32, amplitude: f32) -> Vec<f32> {
+        (0..(secs * RATE as f32) as usize)
+            .map(|i| (TAU * 220.0 * i as f32 / RATE as f32).sin() * amplitude)
+            .collect()
+    }
+
+    fn silence(secs: f32) -> Vec<f32> {
+        vec![0.0; (secs * RATE as f32) as usize]
+    }
+
+    fn detector() -> VoiceActivityDetector {
+        VoiceActivityDetector::new(VadOptions::default(), RATE)
+    }
+
+    #[test]
+    fn stops_after_trailing_silence() {
+        let mut vad = detector();
+        vad.push(&tone(1.0, 0.3));
+        assert!(vad.speech_detected());
+        vad.push(&silence(1.0));
+        assert!(
+            !vad.should_stop(),
+            "1 s of silence is below the 1.5 s default"
+        );
+        vad.push(&silence(0.6));
+        assert!(vad.should_stop());
+    }
+
+    #[test]
+    fn speech_resets_the_silence_timer() {
+        let mut vad = detector();
+        vad.push(&tone(1.0, 0.3));
+        vad.push(&silence(1.2));
+        vad.push(&tone(0.2, 0.3));
+        vad.push(&silence(1.2));
+        assert!(!vad.should_stop());
+    }
+
+    #[test]
+    fn silence_alone_never_stops() {
+        let mut vad = detector();
+        vad.push(&silence(5.0));
+        assert!(!vad.speech_detected());
+        assert!(!vad.should_stop());
+    }
+
+    #[test]
+    fn short_click_does_not_count_as_speech() {
+        let mut vad = detector();
+        vad.push(&tone(0.06, 0.8));
+        vad.push(&silence(3.0));
+        assert!(!vad.should_stop());
+    }
+
+    #[test]
+    fn quiet_input_is_not_speech() {
+        // -50 dBFS hum stays below the -40 dB threshold.
+        let mut vad = detector();
+        vad.push(&tone(2.0, 0.0045));
+        assert!(!vad.speech_detected());
+    }
+
+    #[test]
+    fn loud_broadband_noise_is_not_speech() {
+        let noise: Vec<f32> = (0..RATE as usize).map(|_| fastrand::f32() - 0.5).collect();
+        assert!(zero_crossing_rate(&noise) > MAX_SPEECH_ZCR);
+        let mut vad = detector();
+        vad.push(&noise);
+        assert!(!vad.speech_detected());
+    }
+
+    #[test]
+    fn samples_can_arrive_in_odd_sized_slices() {
+        let mut vad = detector();
+        let audio = [tone(1.0, 0.3), silence(2.0)].concat();
+        for slice in audio.chunks(77) {
+            vad.push(slice);
+        }
+        assert!(vad.should_stop());
+    }
+
+    #[test]
+    fn level_of_full_scale_sine_is_about_minus_3_db() {
+        let level = level_db(&tone(0.1, 1.0));
+        assert!((level + 3.01).abs() < 0.1, "{level}");
+        assert_eq!(level_db(&silence(0.1)), f32::NEG_INFINITY);
+    }
+}
@@ -0,0 +1,333 @@
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender, SyncSender, TryRecvError};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::audio::stream::{self, AudioStream, StreamHandle, StreamWriter};
+use crate::audio::AudioBackend;
+use crate::errors::{Result, VoxputError};
+
+/// Settings for keeping the microphone open between recordings.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WarmMicOptions {
+    /// Audio from just before each recording that is prepended to it, in
+    /// seconds, so the first syllable is not lost while the recording starts.
+    pub preroll_secs: f32,
+    /// How long the microphone stays open after a recording, in seconds,
+    /// before it is released.
+    pub idle_secs: f32,
+}
+
+impl Default for WarmMicOptions {
+    fn default() -> Self {
+        Self {
+            preroll_secs: 0.5,
+            idle_secs: 30.0,
+        }
+    }
+}
+
+/// Bounded buffer holding the most recent samples.
+#[derive(Debug, Clone)]
+pub struct PreRoll {
+    samples: VecDeque<f32>,
+    capacity: usize,
+}
+
+impl PreRoll {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            samples: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// Append `samples`, discarding the oldest ones beyond the capacity.
+    pub fn push(&mut self, samples: &[f32]) {
+        let samples = &samples[samples.len().saturating_sub(self.capacity)..];
+        let excess = (self.samples.len() + samples.len()).saturating_sub(self.capacity);
+        self.samples.drain(..excess);
+        self.samples.extend(samples);
+    }
+
+    /// Remove and return everything held, oldest first.
+    pub fn take(&mut self) -> Vec<f32> {
+        self.samples.drain(..).collect()
+    }
+}
+
+/// A request from [`WarmMic::stream`] to the keeper thread.
+struct Attach {
+    stop: Arc<AtomicBool>,
+    reply: SyncSender<AudioStream>,
+}
+
+/// Keeps an input stream open between recordings, so a recording can start
+/// without waiting for the device and with the audio from just before it.
+///
+/// The microphone is opened by the first recording and then held by a
+/// keeper thread, which fills a [`PreRoll`] while no recording is running.
+/// Once no recording has run for `idle_secs`, the microphone is released
+/// (and desktop privacy indicators go out); the next recording opens it
+/// again, without pre-roll.
+pub struct WarmMic {
+    source: Arc<dyn AudioBackend>,
+    devices: Vec<String>,
+    opts: WarmMicOptions,
+    /// Request channel of the running keeper thread, if the mic is open.
+    keeper: Mutex<Option<Sender<Attach>>>,
+}
+
+impl WarmMic {
+    /// Keep the first available of `devices` (or the default input) of
+    /// `source` warm.
+    pub fn new(source: Arc<dyn AudioBackend>, devices: Vec<String>, opts: WarmMicOptions) -> Self {
+        Self {
+            source,
+            devices,
+            opts,
+            keeper: Mutex::new(None),
+        }
+    }
+
+    /// Start a recording from the open microphone, opening it first if it
+    /// has been released.
+    ///
+    /// The stream starts with up to `preroll_secs` of audio captured before
+    /// the call and ends once `stop` is set or the stream is dropped; the
+    /// microphone itself stays open for the next recording.
+    pub fn stream(&self, stop: Arc<AtomicBool>) -> Result<AudioStream> {
+        let mut keeper = self.keeper.lock().unwrap();
+        for _ in 0..2 {
+            let requests = match keeper.as_ref() {
+                Some(requests) => requests.clone(),
+                None => keeper.insert(self.open()?).clone(),
+            };
+            let (reply_tx, reply_rx) = mpsc::sync_channel(1);
+            let attach = Attach {
+                stop: Arc::clone(&stop),
+                reply: reply_tx,
+            };
+            if requests.send(attach).is_ok() {
+                if let Ok(stream) = reply_rx.recv() {
+                    return Ok(stream);
+                }
+            }
+            // The keeper released the mic (or lost the device) since the
+            // last recording; open it again.
+            *keeper = None;
+        }
+        Err(VoxputError::Audio(
+            "Microphone closed while starting the recording".into(),
+        ))
+    }
+
+    /// Open the device and start a keeper thread holding it.
+    fn open(&self) -> Result<Sender<Attach>> {
+        // The keeper closes the device by dropping its stream.
+        let input = self
+            .source
+            .stream(Arc::new(AtomicBool::new(false)), &self.devices)?;
+        let (requests_tx, requests_rx) = mpsc::channel();
+        let opts = self.opts.clone();
+        std::thread::Builder::new()
+            .name("voxput-warm-mic".into())
+            .spawn(move || keep_warm(input, requests_rx, &opts))
+            .map_err(|e| {
+                VoxputError::Audio(format!("Failed to start warm microphone thread: {e}"))
+            })?;
+        tracing::info!(
+            preroll_secs = self.opts.preroll_secs,
+            "Microphone opened and kept warm"
+        );
+        Ok(requests_tx)
+    }
+}
+
+/// A recording being fed by the keeper.
+struct Recording {
+    writer: StreamWriter,
+    handle: StreamHandle,
+    stop: Arc<AtomicBool>,
+}
+
+/// Read `input` until the mic has been idle for `idle_secs`, the
+/// [`WarmMic`] is dropped or the device fails, feeding frames to the
+/// current recording or else to the pre-roll.
+fn keep_warm(input: AudioStream, requests: Receiver<Attach>, opts: &WarmMicOptions) {
+    let rate = input.sample_rate();
+    let mut preroll = PreRoll::new((opts.preroll_secs.max(0.0) * rate as f32) as usize);
+    let idle = Duration::from_secs_f32(opts.idle_secs.max(0.0));
+    let mut recording: Option<Recording> = None;
+    let mut idle_since = Instant::now();
+
+    for frame in input {
+        let frame = match frame {
+            Ok(frame) => frame,
+            Err(e) => {
+                tracing::warn!("Warm microphone failed: {e}");
+                if let Some(current) = &recording {
+                    current.handle.fail(e.to_string());
+                }
+                return;
+            }
+        };
+
+        match requests.try_recv() {
+            Ok(attach) => {
+                let (mut writer, reader) = stream::channel(rate);
+                writer.push(preroll.take().into_iter());
+                let handle = writer.handle();
+                if attach.reply.send(reader).is_ok() {
+                    recording = Some(Recording {
+                        writer,
+                        handle,
+                        stop: attach.stop,
+                    });
+                }
+            }
+            Err(TryRecvError::Disconnected) if recording.is_none() => return,
+            Err(_) => {}
+        }
+
+        if let Some(current) = recording.as_mut() {
+            if !current.stop.load(Ordering::Relaxed) && current.handle.is_active() {
+                current.writer.push(frame.samples.into_iter());
+                continue;
+            }
+            // Dropping the writer ends the recording's stream.
+            recording = None;
+            idle_since = Instant::now();
+        }
+        preroll.push(&frame.samples);
+        if idle_since.elapsed() >= idle {
+            tracing::info!("Releasing idle microphone");
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::DeviceInfo;
+    use std::sync::atomic::AtomicUsize;
+    use std::thread;
+
+    const RATE: u32 = 1000;
+
+    /// Produces a ramp (0, 1, 2, …) in real time, counting opens and closes.
+    #[derive(Default)]
+    struct RampBackend {
+        produced: Arc<AtomicUsize>,
+        opened: AtomicUsize,
+        closed: Arc<AtomicBool>,
+    }
+
+    impl AudioBackend for RampBackend {
+        fn list_devices(&self) -> Result<Vec<DeviceInfo>> {
+            Ok(Vec::new())
+        }
+
+        fn stream(&self, _: Arc<AtomicBool>, _: &[String]) -> Result<AudioStream> {
+            self.opened.fetch_add(1, Ordering::SeqCst);
+            self.closed.store(false, Ordering::SeqCst);
+            let (mut writer, reader) = stream::channel(RATE);
+            let (produced, closed) = (Arc::clone(&self.produced), Arc::clone(&self.closed));
+            thread::spawn(move || {
+                let handle = writer.handle();
+                while handle.is_active() {
+                    let start = produced.fetch_add(10, Ordering::SeqCst);
+                    writer.push((start..start + 10).map(|i| i as f32));
+                    thread::sleep(Duration::from_millis(10));
+                }
+                closed.store(true, Ordering::SeqCst);
+            });
+            Ok(reader)
+        }
+    }
+
+    fn warm(source: &Arc<RampBackend>, preroll_secs: f32, idle_secs: f32) -> WarmMic {
+        let source: Arc<dyn AudioBackend> = source.clone();
+        WarmMic::new(
+            source,
+            Vec::new(),
+            WarmMicOptions {
+                preroll_secs,
+                idle_secs,
+            },
+        )
+    }
+
+    /// Record from `mic` for about `ms` milliseconds.
+    fn record(mic: &WarmMic, ms: u64) -> Vec<f32> {
+        let stop = Arc::new(AtomicBool::new(false));
+        let stream = mic.stream(Arc::clone(&stop)).unwrap();
+        let setter = Arc::clone(&stop);
+        thread::spawn(move || {
+            thread::sleep(Duration::from_millis(ms));
+            setter.store(true, Ordering::SeqCst);
+        });
+        stream.flat_map(|f| f.unwrap().samples).collect()
+    }
+
+    #[test]
+    fn preroll_keeps_only_the_latest_samples() {
+        let mut preroll = PreRoll::new(4);
+        preroll.push(&[1.0, 2.0, 3.0]);
+        preroll.push(&[4.0, 5.0]);
+        assert_eq!(preroll.take(), vec![2.0, 3.0, 4.0, 5.0]);
+        preroll.push(&[1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+        assert_eq!(preroll.take(), vec![3.0, 4.0, 5.0, 6.0]);
+        assert!(preroll.take().is_empty());
+    }
+
+    #[test]
+    fn recordings_start_with_the_audio_before_them() {
+        let source = Arc::new(RampBackend::default());
+        let mic = warm(&source, 0.1, 10.0);
+        record(&mic, 50);
+        thread::sleep(Duration::from_millis(300));
+
+        let requested_at = source.produced.load(Ordering::SeqCst) as f32;
+        let samples = record(&mic, 100);
+        assert!(
+            samples[0] + 50.0 < requested_at,
+            "starts {} before {requested_at}",
+            samples[0]
+        );
+        assert!(samples.windows(2).all(|w| w[1] == w[0] + 1.0), "no gaps");
+        assert!(samples.len() >= 100);
+        assert_eq!(
+            source.opened.load(Ordering::SeqCst),
+            1,
+            "the device stayed open"
+        );
+    }
+
+    #[test]
+    fn idle_microphone_is_released_and_reopened() {
+        let source = Arc::new(RampBackend::default());
+        let mic = warm(&source, 0.1, 0.1);
+        record(&mic, 20);
+        thread::sleep(Duration::from_millis(400));
+        assert!(
+            source.closed.load(Ordering::SeqCst),
+            "released after idling"
+        );
+
+        assert!(!record(&mic, 50).is_empty());
+        assert_eq!(source.opened.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn dropping_the_warm_mic_closes_the_device() {
+        let source = Arc::new(RampBackend::default());
+        let mic = warm(&source, 0.1, 10.0);
+        record(&mic, 20);
+        drop(mic);
+        thread::sleep(Duration::from_millis(200));
+        assert!(source.closed.load(Ordering::SeqCst));
+    }
+}
@@ -39,13 +39,17 @@ pub fn encode_wav(audio: &AudioData) -> Result<Vec<u8>> {
 
     // Sample data
     for &sample in &audio.samples {
-        let int_sample = (sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16;
-        buf.extend_from_slice(&int_sample.to_le_bytes());
+        buf.extend_from_slice(&to_i16(sample).to_le_bytes());
     }
 
     Ok(buf)
 }
 
+/// Convert an f32 sample to 16-bit PCM, clamping out-of-range values.
+pub(crate) fn to_i16(sample: f32) -> i16 {
+    (sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,29 +1,57 @@
 pub mod schema;
 
+use crate::audio::chunk::ChunkOptions;
+use crate::audio::convert::ChannelMix;
+use crate::audio::device::parse_preferences;
+use crate::audio::dsp::DspOptions;
+use crate::audio::encode::UploadCodec;
+use crate::audio::segment::SegmentOptions;
+use crate::audio::source::AudioSource;
+use crate::audio::trim::TrimOptions;
+use crate::audio::vad::VadOptions;
+use crate::audio::warm::WarmMicOptions;
+use crate::audio::AudioBackend;
 use crate::errors::{Result, VoxputError};
+use crate::guard::GuardOptions;
+use crate::provider::retry::RetryPolicy;
+use crate::provider::{DEFAULT_CONNECT_TIMEOUT, DEFAULT_REQUEST_TIMEOUT, PROVIDER_NAMES};
 use schema::FileConfig;
 use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
 
-/// Fully resolved runtime configuration.
+/// Lowest `audio.sample_rate` accepted; lower rates lose too much of speech.
+const MIN_SAMPLE_RATE: u32 = 8000;
+
+/// Resolved settings for a single transcription provider.
 #[derive(Debug, Clone)]
-pub struct ResolvedConfig {
+pub struct ProviderSettings {
     /// Name of the env var that holds the API key.
     pub api_key_env: String,
     /// Pre-resolved API key (from file or env var).
     pub api_key: Option<String>,
     /// Transcription model name.
     pub model: Option<String>,
-    /// Provider name ("groq").
-    pub provider: String,
-    /// Preferred audio input device name.
-    pub device: Option<String>,
-    /// Audio sample rate.
-    pub sample_rate: u32,
-    /// Default output target.
-    pub output_target: String,
+    /// Translation model name (Groq provider only).
+    pub translation_model: Option<String>,
+    /// Endpoint base URL (OpenAI-compatible provider only).
+    pub base_url: Option<String>,
+    /// Header carrying the API key (OpenAI-compatible provider only).
+    pub auth_header: Option<String>,
 }
 
-impl ResolvedConfig {
+impl ProviderSettings {
+    fn with_key_env(api_key_env: &str) -> Self {
+        Self {
+            api_key_env: api_key_env.to_string(),
+            api_key: None,
+            model: None,
+            translation_model: None,
+            base_url: None,
+            auth_header: None,
+        }
+    }
+
     /// Return the API key, checking the pre-resolved field then the env var.
     pub fn api_key(&self) -> Result<String> {
         if let Some(ref key) = self.api_key {
@@ -35,21 +63,129 @@ impl ResolvedConfig {
     }
 }
 
+/// Fully resolved runtime configuration.
+#[derive(Debug, Clone)]
+pub struct ResolvedConfig {
+    /// Provider name (one of [`PROVIDER_NAMES`]).
+    pub provider: String,
+    /// Providers to fall back to, in order, on retryable failures.
+    pub fallback: Vec<String>,
+    /// Model override for the selected provider (`VOXPUT_MODEL` / `--model`).
+    pub model: Option<String>,
+    /// Settings for the Groq provider.
+    pub groq: ProviderSettings,
+    /// Settings for the OpenAI-compatible provider.
+    pub openai: ProviderSettings,
+    /// Retry behaviour for transcription requests.
+    pub retry: RetryPolicy,
+    /// Time allowed to connect to a provider.
+    pub connect_timeout: Duration,
+    /// Time allowed for a single transcription request.
+    pub request_timeout: Duration,
+    /// Request verbose transcripts (segments, word timestamps, language).
+    pub verbose: bool,
+    /// Translate speech into English instead of transcribing it.
+    pub translate: bool,
+    /// How long recordings are split before upload.
+    pub chunking: ChunkOptions,
+    /// Drop transcripts of silent audio and known hallucinated phrases.
+    pub hallucination_guard: bool,
+    /// Settings for `hallucination_guard`.
+    pub guard: GuardOptions,
+    /// Transcribe recordings in segments while they are still being captured.
+    pub incremental: bool,
+    /// Where `incremental` cuts recordings.
+    pub segments: SegmentOptions,
+    /// Where recordings come from (`VOXPUT_AUDIO_SOURCE`).
+    pub source: AudioSource,
+    /// Playback speed of a non-device `source` (`VOXPUT_AUDIO_SPEED`); 0 is unpaced.
+    pub source_speed: f32,
+    /// Input device patterns in order of preference; empty uses the system default.
+    pub devices: Vec<String>,
+    /// Which input channel is recorded, and the gain of each.
+    pub channel_mix: ChannelMix,
+    /// Audio sample rate.
+    pub sample_rate: u32,
+    /// Codec used to upload audio to the provider.
+    pub upload_codec: UploadCodec,
+    /// Processing applied to recordings before upload.
+    pub dsp: DspOptions,
+    /// Cut silence from both ends of recordings and reject silent ones.
+    pub trim_silence: bool,
+    /// Settings for `trim_silence`.
+    pub trim: TrimOptions,
+    /// End recordings automatically once the speaker stops talking.
+    pub auto_stop: bool,
+    /// Voice activity detection settings used by `auto_stop`.
+    pub vad: VadOptions,
+    /// Keep the microphone open between daemon recordings.
+    pub warm_mic: bool,
+    /// Settings for `warm_mic`.
+    pub warm: WarmMicOptions,
+    /// Default output target.
+    pub output_target: String,
+}
+
+impl Default for ResolvedConfig {
+    fn default() -> Self {
+        Self {
+            provider: "groq".to_string(),
+            fallback: Vec::new(),
+            model: None,
+            groq: ProviderSettings::with_key_env("GROQ_API_KEY"),
+            openai: ProviderSettings::with_key_env("OPENAI_API_KEY"),
+            retry: RetryPolicy::default(),
+            connect_timeout: DEFAULT_CONNECT_TIMEOUT,
+            request_timeout: DEFAULT_REQUEST_TIMEOUT,
+            verbose: false,
+            translate: false,
+            chunking: ChunkOptions::default(),
+            hallucination_guard: true,
+            guard: GuardOptions::default(),
+            incremental: false,
+            segments: SegmentOptions::default(),
+            source: AudioSource::Device,
+            source_speed: 1.0,
+            devices: Vec::new(),
+            channel_mix: ChannelMix::default(),
+            sample_rate: 16000,
+            upload_codec: UploadCodec::default(),
+            dsp: DspOptions::default(),
+            trim_silence: true,
+            trim: TrimOptions::default(),
+            auto_stop: false,
+            vad: VadOptions::default(),
+            warm_mic: false,
+            warm: WarmMicOptions::default(),
+            output_target: "stdout".to_string(),
+        }
+    }
+}
+
+impl ResolvedConfig {
+    /// Settings for the named provider, or `None` if the name is unknown.
+    pub fn provider_settings(&self, name: &str) -> Option<&ProviderSettings> {
+        match name {
+            "groq" => Some(&self.groq),
+            "openai" => Some(&self.openai),
+            _ => None,
+        }
+    }
+
+    /// Backend standing in for the input device when `source` is not
+    /// [`AudioSource::Device`].
+    pub fn source_backend(&self) -> Option<Arc<dyn AudioBackend>> {
+        self.source.backend(self.sample_rate, self.source_speed)
+    }
+}
+
 fn config_path() -> Option<PathBuf> {
     dirs::config_dir().map(|d| d.join("voxput").join("config.toml"))
 }
 
 /// Load configuration: defaults → file → env vars.
 pub fn load_config() -> Result<ResolvedConfig> {
-    let mut resolved = ResolvedConfig {
-        api_key_env: "GROQ_API_KEY".to_string(),
-        api_key: None,
-        model: None,
-        provider: "groq".to_string(),
-        device: None,
-        sample_rate: 16000,
-        output_target: "stdout".to_string(),
-    };
+    let mut resolved = ResolvedConfig::default();
 
     // Layer 2: file config
     if let Some(path) = config_path() {
@@ -68,9 +204,11 @@ pub fn load_config() -> Result<ResolvedConfig> {
     }
 
     // Layer 3: env var overrides
-    if let Ok(key) = std::env::var(&resolved.api_key_env) {
-        if !key.is_empty() {
-            resolved.api_key = Some(key);
+    for settings in [&mut resolved.groq, &mut resolved.openai] {
+        if let Ok(key) = std::env::var(&settings.api_key_env) {
+            if !key.is_empty() {
+                settings.api_key = Some(key);
+            }
         }
     }
     if let Ok(model) = std::env::var("VOXPUT_MODEL") {
@@ -78,33 +216,193 @@ pub fn load_config() -> Result<ResolvedConfig> {
             resolved.model = Some(model);
         }
     }
+    if let Ok(source) = std::env::var("VOXPUT_AUDIO_SOURCE") {
+        if !source.is_empty() {
+            resolved.source = source
+                .parse()
+                .map_err(|e| VoxputError::Config(format!("VOXPUT_AUDIO_SOURCE: {e}")))?;
+        }
+    }
+    if let Ok(speed) = std::env::var("VOXPUT_AUDIO_SPEED") {
+        if !speed.is_empty() {
+            let speed: f32 = speed.parse().map_err(|_| {
+                VoxputError::Config(format!("VOXPUT_AUDIO_SPEED: invalid speed {speed:?}"))
+            })?;
+            resolved.source_speed = speed.max(0.0);
+        }
+    }
 
+    validate(&resolved)?;
     Ok(resolved)
 }
 
+fn validate(r: &ResolvedConfig) -> Result<()> {
+    parse_preferences(&r.devices)?;
+    if r.sample_rate < MIN_SAMPLE_RATE {
+        return Err(VoxputError::Config(format!(
+            "audio.sample_rate must be at least {MIN_SAMPLE_RATE} Hz, got {}",
+            r.sample_rate
+        )));
+    }
+    for name in std::iter::once(&r.provider).chain(&r.fallback) {
+        if r.provider_settings(name).is_none() {
+            return Err(VoxputError::UnknownProvider {
+                name: name.clone(),
+                valid: PROVIDER_NAMES.join(", "),
+            });
+        }
+    }
+    Ok(())
+}
+
 fn apply_file_config(r: &mut ResolvedConfig, f: &FileConfig) {
     if let Some(ref p) = f.provider {
         r.provider = p.clone();
     }
+    if let Some(ref fallback) = f.fallback {
+        r.fallback = fallback.clone();
+    }
     if let Some(ref groq) = f.providers.groq {
         if let Some(ref env) = groq.api_key_env {
-            r.api_key_env = env.clone();
+            r.groq.api_key_env = env.clone();
         }
         if let Some(ref key) = groq.api_key {
             if !key.is_empty() {
-                r.api_key = Some(key.clone());
+                r.groq.api_key = Some(key.clone());
             }
         }
         if let Some(ref model) = groq.model {
-            r.model = Some(model.clone());
+            r.groq.model = Some(model.clone());
+        }
+        if let Some(ref model) = groq.translation_model {
+            r.groq.translation_model = Some(model.clone());
+        }
+    }
+    if let Some(ref openai) = f.providers.openai {
+        if let Some(ref env) = openai.api_key_env {
+            r.openai.api_key_env = env.clone();
+        }
+        if let Some(ref key) = openai.api_key {
+            if !key.is_empty() {
+                r.openai.api_key = Some(key.clone());
+            }
+        }
+        if let Some(ref model) = openai.model {
+            r.openai.model = Some(model.clone());
+        }
+        if let Some(ref url) = openai.base_url {
+            r.openai.base_url = Some(url.clone());
         }
+        if let Some(ref header) = openai.auth_header {
+            r.openai.auth_header = Some(header.clone());
+        }
+    }
+    if let Some(attempts) = f.transcription.max_attempts {
+        r.retry.max_attempts = attempts.max(1);
+    }
+    if let Some(secs) = f.transcription.retry_deadline_secs {
+        r.retry.deadline = Duration::from_secs_f32(secs.max(0.0));
+    }
+    if let Some(secs) = f.transcription.connect_timeout_secs {
+        r.connect_timeout = Duration::from_secs_f32(secs.max(0.0));
+    }
+    if let Some(secs) = f.transcription.timeout_secs {
+        r.request_timeout = Duration::from_secs_f32(secs.max(0.0));
+    }
+    if let Some(verbose) = f.transcription.verbose {
+        r.verbose = verbose;
+    }
+    if let Some(translate) = f.transcription.translate {
+        r.translate = translate;
+    }
+    if let Some(secs) = f.transcription.chunk_secs {
+        r.chunking.max_chunk_secs = secs.max(1.0);
+    }
+    if let Some(secs) = f.transcription.chunk_overlap_secs {
+        r.chunking.overlap_secs = secs.max(0.0);
+    }
+    if let Some(n) = f.transcription.concurrency {
+        r.chunking.concurrency = n.max(1);
+    }
+    if let Some(guard) = f.transcription.hallucination_guard {
+        r.hallucination_guard = guard;
+    }
+    if let Some(prob) = f.transcription.no_speech_threshold {
+        r.guard.no_speech_threshold = prob.clamp(0.0, 1.0);
     }
-    if let Some(ref dev) = f.audio.device {
-        r.device = Some(dev.clone());
+    if let Some(ref phrases) = f.transcription.hallucination_blocklist {
+        r.guard.blocklist = phrases.clone();
+    }
+    if let Some(incremental) = f.transcription.incremental {
+        r.incremental = incremental;
+    }
+    if let Some(secs) = f.transcription.segment_pause_secs {
+        r.segments.pause_secs = secs.max(0.1);
+    }
+    if let Some(secs) = f.transcription.max_segment_secs {
+        r.segments.max_segment_secs = secs.max(1.0);
+    }
+    if let Some(ref source) = f.audio.source {
+        r.source = source.clone();
+    }
+    if let Some(speed) = f.audio.source_speed {
+        r.source_speed = speed.max(0.0);
+    }
+    if let Some(ref devices) = f.audio.device {
+        r.devices = devices.to_vec();
+    }
+    if let Some(channel) = f.audio.channel {
+        r.channel_mix.channel = channel;
+    }
+    if let Some(ref gains) = f.audio.channel_gain_db {
+        r.channel_mix.gains = gains.iter().map(|db| 10f32.powf(db / 20.0)).collect();
     }
     if let Some(rate) = f.audio.sample_rate {
         r.sample_rate = rate;
     }
+    if let Some(codec) = f.audio.upload_codec {
+        r.upload_codec = codec;
+    }
+    let dsp = &f.audio.dsp;
+    if let Some(hz) = dsp.high_pass_hz {
+        r.dsp.high_pass_hz = (hz > 0.0).then_some(hz);
+    }
+    if let Some(db) = dsp.noise_gate_db {
+        r.dsp.noise_gate_db = Some(db);
+    }
+    if let Some(mode) = dsp.normalize {
+        r.dsp.normalize = mode;
+    }
+    if let Some(db) = dsp.target_db {
+        r.dsp.target_db = Some(db);
+    }
+    if let Some(db) = dsp.max_gain_db {
+        r.dsp.max_gain_db = db.max(0.0);
+    }
+    if let Some(trim) = f.audio.trim_silence {
+        r.trim_silence = trim;
+    }
+    if let Some(auto_stop) = f.audio.auto_stop {
+        r.auto_stop = auto_stop;
+    }
+    if let Some(secs) = f.audio.silence_secs {
+        r.vad.trailing_silence_secs = secs.max(0.1);
+    }
+    if let Some(db) = f.audio.vad_threshold_db {
+        r.vad.threshold_db = db;
+        r.trim.threshold_db = db;
+        r.guard.threshold_db = db;
+        r.segments.threshold_db = db;
+    }
+    if let Some(warm) = f.audio.warm_mic {
+        r.warm_mic = warm;
+    }
+    if let Some(ms) = f.audio.preroll_ms {
+        r.warm.preroll_secs = ms.min(1000) as f32 / 1000.0;
+    }
+    if let Some(secs) = f.audio.warm_idle_secs {
+        r.warm.idle_secs = secs.max(0.0);
+    }
     if let Some(ref tgt) = f.output.target {
         r.output_target = tgt.clone();
     }
@@ -113,66 +411,39 @@ fn apply_file_config(r: &mut ResolvedConfig, f: &FileConfig) {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::audio::convert::ChannelSelect;
+    use crate::audio::dsp::Normalize;
 
     #[test]
     fn default_config_has_sensible_values() {
-        // Call load_config with no file (will use whatever the test env has)
-        // We can at least check the built-in defaults by constructing manually
-        let cfg = ResolvedConfig {
-            api_key_env: "GROQ_API_KEY".to_string(),
-            api_key: None,
-            model: None,
-            provider: "groq".to_string(),
-            device: None,
-            sample_rate: 16000,
-            output_target: "stdout".to_string(),
-        };
+        let cfg = ResolvedConfig::default();
         assert_eq!(cfg.provider, "groq");
+        assert_eq!(cfg.groq.api_key_env, "GROQ_API_KEY");
+        assert_eq!(cfg.openai.api_key_env, "OPENAI_API_KEY");
         assert_eq!(cfg.sample_rate, 16000);
         assert_eq!(cfg.output_target, "stdout");
     }
 
     #[test]
     fn api_key_resolved_from_field() {
-        let cfg = ResolvedConfig {
-            api_key_env: "GROQ_API_KEY".to_string(),
+        let settings = ProviderSettings {
             api_key: Some("my-key".to_string()),
-            model: None,
-            provider: "groq".to_string(),
-            device: None,
-            sample_rate: 16000,
-            output_target: "stdout".to_string(),
+            ..ProviderSettings::with_key_env("GROQ_API_KEY")
         };
-        assert_eq!(cfg.api_key().unwrap(), "my-key");
+        assert_eq!(settings.api_key().unwrap(), "my-key");
     }
 
     #[test]
     fn api_key_missing_returns_error() {
         // Use an env var that almost certainly doesn't exist
-        let cfg = ResolvedConfig {
-            api_key_env: "VOXPUT_TEST_MISSING_KEY_XYZ".to_string(),
-            api_key: None,
-            model: None,
-            provider: "groq".to_string(),
-            device: None,
-            sample_rate: 16000,
-            output_target: "stdout".to_string(),
-        };
-        let err = cfg.api_key().expect_err("should fail on missing key");
+        let settings = ProviderSettings::with_key_env("VOXPUT_TEST_MISSING_KEY_XYZ");
+        let err = settings.api_key().expect_err("should fail on missing key");
         assert!(err.to_string().contains("VOXPUT_TEST_MISSING_KEY_XYZ"));
     }
 
     #[test]
     fn apply_file_config_overrides_defaults() {
-        let mut resolved = ResolvedConfig {
-            api_key_env: "GROQ_API_KEY".to_string(),
-            api_key: None,
-            model: None,
-            provider: "groq".to_string(),
-            device: None,
-            sample_rate: 16000,
-            output_target: "stdout".to_string(),
-        };
+        let mut resolved = ResolvedConfig::default();
         let file = schema::FileConfig::from_toml(
             r#"
 [providers.groq]
@@ -185,7 +456,250 @@ sample_rate = 8000
         .unwrap();
 
         apply_file_config(&mut resolved, &file);
-        assert_eq!(resolved.model.as_deref(), Some("whisper-large-v3"));
+        assert_eq!(resolved.groq.model.as_deref(), Some("whisper-large-v3"));
         assert_eq!(resolved.sample_rate, 8000);
     }
+
+    /// The defaults with the config file `toml` applied.
+    fn applied(toml: &str) -> ResolvedConfig {
+        let mut resolved = ResolvedConfig::default();
+        apply_file_config(&mut resolved, &schema::FileConfig::from_toml(toml).unwrap());
+        resolved
+    }
+
+    #[test]
+    fn groq_translation_model_is_applied() {
+        let resolved = applied("[providers.groq]\ntranslation_model = \"whisper-large-v2\"");
+        assert_eq!(
+            resolved.groq.translation_model.as_deref(),
+            Some("whisper-large-v2")
+        );
+    }
+
+    #[test]
+    fn retry_and_timeout_settings_are_applied() {
+        let resolved = applied(
+            r#"
+[transcription]
+max_attempts = 0
+retry_deadline_secs = 10
+timeout_secs = 45
+"#,
+        );
+        assert_eq!(
+            resolved.retry.max_attempts, 1,
+            "zero attempts clamps to one"
+        );
+        assert_eq!(resolved.retry.deadline, Duration::from_secs(10));
+        assert_eq!(resolved.request_timeout, Duration::from_secs(45));
+        assert_eq!(resolved.connect_timeout, DEFAULT_CONNECT_TIMEOUT);
+    }
+
+    #[test]
+    fn chunking_section_is_applied() {
+        let resolved = applied("[transcription]\nchunk_secs = 300\nconcurrency = 0");
+        assert_eq!(resolved.chunking.max_chunk_secs, 300.0);
+        assert_eq!(resolved.chunking.overlap_secs, 2.0);
+        assert_eq!(
+            resolved.chunking.concurrency, 1,
+            "zero concurrency clamps to one"
+        );
+    }
+
+    #[test]
+    fn upload_codec_is_applied() {
+        let resolved = applied("[audio]\nupload_codec = \"flac\"");
+        assert_eq!(resolved.upload_codec, UploadCodec::Flac);
+    }
+
+    #[test]
+    fn auto_stop_and_speech_threshold_are_applied() {
+        let resolved = applied(
+            r#"
+[audio]
+auto_stop = true
+silence_secs = 0
+vad_threshold_db = -30
+"#,
+        );
+        assert!(resolved.auto_stop);
+        assert_eq!(
+            resolved.vad.trailing_silence_secs, 0.1,
+            "silence clamps to 100 ms"
+        );
+        assert_eq!(resolved.vad.threshold_db, -30.0);
+        assert_eq!(
+            resolved.trim.threshold_db, -30.0,
+            "threshold applies to trimming too"
+        );
+        assert_eq!(resolved.guard.threshold_db, -30.0);
+        assert_eq!(resolved.segments.threshold_db, -30.0);
+    }
+
+    #[test]
+    fn trim_silence_is_applied() {
+        assert!(!applied("[audio]\ntrim_silence = false").trim_silence);
+    }
+
+    #[test]
+    fn hallucination_guard_settings_are_applied() {
+        let resolved = applied(
+            r#"
+[transcription]
+no_speech_threshold = 1.5
+hallucination_blocklist = ["okay"]
+"#,
+        );
+        assert!(resolved.hallucination_guard);
+        assert_eq!(resolved.guard.no_speech_threshold, 1.0);
+        assert_eq!(resolved.guard.blocklist, vec!["okay".to_string()]);
+    }
+
+    #[test]
+    fn dsp_section_is_applied() {
+        let resolved = applied(
+            r#"
+[audio.dsp]
+high_pass_hz = 0
+noise_gate_db = -50
+normalize = "off"
+"#,
+        );
+        assert_eq!(
+            resolved.dsp.high_pass_hz, None,
+            "zero cutoff disables the filter"
+        );
+        assert_eq!(resolved.dsp.noise_gate_db, Some(-50.0));
+        assert_eq!(resolved.dsp.normalize, Normalize::Off);
+        assert_eq!(resolved.dsp.max_gain_db, 20.0);
+    }
+
+    #[test]
+    fn incremental_settings_are_applied() {
+        let resolved = applied(
+            r#"
+[transcription]
+incremental = true
+segment_pause_secs = 0.8
+max_segment_secs = 0
+"#,
+        );
+        assert!(resolved.incremental);
+        assert_eq!(resolved.segments.pause_secs, 0.8);
+        assert_eq!(
+            resolved.segments.max_segment_secs, 1.0,
+            "segments last at least a second"
+        );
+    }
+
+    #[test]
+    fn warm_mic_settings_are_applied() {
+        let resolved = applied("[audio]\nwarm_mic = true\npreroll_ms = 5000");
+        assert!(resolved.warm_mic);
+        assert_eq!(
+            resolved.warm.preroll_secs, 1.0,
+            "pre-roll is capped at a second"
+        );
+        assert_eq!(resolved.warm.idle_secs, 30.0);
+    }
+
+    #[test]
+    fn channel_settings_are_applied() {
+        let resolved = applied("[audio]\nchannel = 1\nchannel_gain_db = [0, 6]");
+        assert_eq!(resolved.channel_mix.channel, ChannelSelect::Index(1));
+        assert_eq!(resolved.channel_mix.gains[0], 1.0);
+        assert!((resolved.channel_mix.gains[1] - 1.995).abs() < 1e-3);
+    }
+
+    #[test]
+    fn audio_source_is_applied() {
+        let resolved = applied("[audio]\nsource = \"tone:1000\"\nsource_speed = -2");
+        assert_eq!(resolved.source, AudioSource::Tone(1000.0));
+        assert_eq!(
+            resolved.source_speed, 0.0,
+            "negative speed clamps to unpaced"
+        );
+    }
+
+    #[test]
+    fn apply_file_config_reads_openai_section() {
+        let mut resolved = ResolvedConfig::default();
+        let file = schema::FileConfig::from_toml(
+            r#"
+provider = "openai"
+
+[providers.openai]
+base_url = "http://inference.internal:8000/v1"
+api_key_env = "INFERENCE_KEY"
+auth_header = "api-key"
+model = "large-v3"
+"#,
+        )
+        .unwrap();
+
+        apply_file_config(&mut resolved, &file);
+        assert_eq!(resolved.provider, "openai");
+        let openai = resolved.provider_settings("openai").unwrap();
+        assert_eq!(
+            openai.base_url.as_deref(),
+            Some("http://inference.internal:8000/v1")
+        );
+        assert_eq!(openai.api_key_env, "INFERENCE_KEY");
+        assert_eq!(openai.auth_header.as_deref(), Some("api-key"));
+        assert_eq!(openai.model.as_deref(), Some("large-v3"));
+        assert!(validate(&resolved).is_ok());
+    }
+
+    #[test]
+    fn unknown_fallback_provider_fails_validation() {
+        let resolved = ResolvedConfig {
+            fallback: vec!["openai".to_string(), "azure".to_string()],
+            ..ResolvedConfig::default()
+        };
+        let err = validate(&resolved).expect_err("unknown fallback should be rejected");
+        assert!(err.to_string().contains("azure"));
+    }
+
+    #[test]
+    fn unknown_provider_fails_validation_listing_choices() {
+        let resolved = ResolvedConfig {
+            provider: "whisperx".to_string(),
+            ..ResolvedConfig::default()
+        };
+        let err = validate(&resolved).expect_err("unknown provider should be rejected");
+        let msg = err.to_string();
+        assert!(msg.contains("whisperx"), "Expected provider name in: {msg}");
+        assert!(
+            msg.contains("groq") && msg.contains("openai"),
+            "Expected choices in: {msg}"
+        );
+    }
+
+    #[test]
+    fn invalid_device_regex_fails_validation() {
+        let resolved = ResolvedConfig {
+            devices: vec!["jabra".to_string(), "/(usb/".to_string()],
+            ..ResolvedConfig::default()
+        };
+        let err = validate(&resolved).expect_err("bad regex should be rejected");
+        assert!(err.to_string().contains("/(usb/"));
+    }
+
+    #[test]
+    fn sample_rate_below_8000_fails_validation() {
+        for rate in [0, 7999] {
+            let resolved = ResolvedConfig {
+                sample_rate: rate,
+                ..ResolvedConfig::default()
+            };
+            let err = validate(&resolved).expect_err("low sample rate should be rejected");
+            assert!(matches!(err, VoxputError::Config(_)), "{err:?}");
+            assert!(err.to_string().contains("sample_rate"), "{err}");
+        }
+        let resolved = ResolvedConfig {
+            sample_rate: 8000,
+            ..ResolvedConfig::default()
+        };
+        assert!(validate(&resolved).is_ok());
+    }
 }
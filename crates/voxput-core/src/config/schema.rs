@@ -1,14 +1,26 @@
 use serde::Deserialize;
 
+use crate::audio::convert::ChannelSelect;
+use crate::audio::dsp::Normalize;
+use crate::audio::encode::UploadCodec;
+use crate::audio::source::AudioSource;
+
 /// TOML-deserializable config file format.
 #[derive(Debug, Clone, Deserialize, Default)]
 pub struct FileConfig {
     #[serde(default)]
     pub provider: Option<String>,
 
+    /// Providers to try, in order, when `provider` fails with a retryable error.
+    #[serde(default)]
+    pub fallback: Option<Vec<String>>,
+
     #[serde(default)]
     pub providers: ProvidersConfig,
 
+    #[serde(default)]
+    pub transcription: TranscriptionConfig,
+
     #[serde(default)]
     pub audio: AudioConfig,
 
@@ -20,6 +32,8 @@ pub struct FileConfig {
 pub struct ProvidersConfig {
     #[serde(default)]
     pub groq: Option<GroqConfig>,
+    #[serde(default)]
+    pub openai: Option<OpenAiConfig>,
 }
 
 #[derive(Debug, Clone, Deserialize, Default)]
@@ -30,14 +44,123 @@ pub struct GroqConfig {
     pub api_key: Option<String>,
     /// Model name (e.g. "whisper-large-v3-turbo").
     pub model: Option<String>,
+    /// Model used for translations (default: "whisper-large-v3").
+    pub translation_model: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Default)]
+pub struct OpenAiConfig {
+    /// Endpoint base URL (default: "https://api.openai.com/v1").
+    pub base_url: Option<String>,
+    /// Name of the env var that holds the API key (default: "OPENAI_API_KEY").
+    pub api_key_env: Option<String>,
+    /// Directly embedded API key (not recommended; prefer env var).
+    pub api_key: Option<String>,
+    /// Header carrying the API key (default: "Authorization", sent as a bearer token).
+    pub auth_header: Option<String>,
+    /// Model name (default: "whisper-1").
+    pub model: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Default)]
+pub struct TranscriptionConfig {
+    /// Maximum requests per transcription, including the first (default 3; 1 disables retries).
+    pub max_attempts: Option<u32>,
+    /// Overall time budget for retries in seconds (default 30).
+    pub retry_deadline_secs: Option<f32>,
+    /// Time allowed to connect to the provider in seconds (default 10).
+    pub connect_timeout_secs: Option<f32>,
+    /// Time allowed for a single request in seconds (default 120).
+    pub timeout_secs: Option<f32>,
+    /// Request verbose transcripts with segment and word timestamps (default false).
+    pub verbose: Option<bool>,
+    /// Translate speech into English instead of transcribing it (default false).
+    pub translate: Option<bool>,
+    /// Longest audio chunk sent in one request, in seconds (default 600).
+    pub chunk_secs: Option<f32>,
+    /// Audio shared between neighbouring chunks, in seconds (default 2).
+    pub chunk_overlap_secs: Option<f32>,
+    /// Number of chunks transcribed at the same time (default 1).
+    pub concurrency: Option<usize>,
+    /// Drop transcripts of silence and known hallucinated phrases (default true).
+    pub hallucination_guard: Option<bool>,
+    /// `no_speech_prob` at or above which a low-confidence segment is dropped (default 0.6).
+    pub no_speech_threshold: Option<f64>,
+    /// Whole transcripts treated as hallucinations; replaces the built-in list.
+    pub hallucination_blocklist: Option<Vec<String>>,
+    /// Transcribe recordings in segments, cut at pauses, while still recording (default false).
+    pub incremental: Option<bool>,
+    /// Pause that ends an incremental segment, in seconds (default 0.6).
+    pub segment_pause_secs: Option<f32>,
+    /// Longest incremental segment, in seconds (default 30).
+    pub max_segment_secs: Option<f32>,
 }
 
 #[derive(Debug, Clone, Deserialize, Default)]
 pub struct AudioConfig {
-    /// Preferred input device name.
-    pub device: Option<String>,
-    /// Sample rate in Hz (default 16000).
+    /// Where recordings come from: "device" (default), "file:<path>", "tone", "tone:<hz>" or "silence".
+    pub source: Option<AudioSource>,
+    /// Playback speed of a non-device source relative to real time; 0 is unpaced (default 1).
+    pub source_speed: Option<f32>,
+    /// Preferred input device, or an ordered list of them: names matched as
+    /// case-insensitive substrings, or regular expressions written `/expr/`.
+    pub device: Option<DeviceList>,
+    /// Input channel to record, counted from 0, or "mix" to average all of them (default "mix").
+    pub channel: Option<ChannelSelect>,
+    /// Gain per input channel in dB, by index, applied before `channel` (default 0 for each).
+    pub channel_gain_db: Option<Vec<f32>>,
+    /// Sample rate in Hz, at least 8000 (default 16000).
     pub sample_rate: Option<u32>,
+    /// Upload codec: "wav" (default) or "flac".
+    pub upload_codec: Option<UploadCodec>,
+    /// Trim silence from both ends of recordings and reject silent ones (default true).
+    pub trim_silence: Option<bool>,
+    /// Stop recording once the speaker falls silent (default false).
+    pub auto_stop: Option<bool>,
+    /// Silence after speech that ends an auto-stopped recording, in seconds (default 1.5).
+    pub silence_secs: Option<f32>,
+    /// Level in dBFS above which audio may count as speech (default -40).
+    pub vad_threshold_db: Option<f32>,
+    /// Keep the microphone open between daemon recordings (default false).
+    pub warm_mic: Option<bool>,
+    /// Audio from before each warm-mic recording prepended to it, in milliseconds (default 500, at most 1000).
+    pub preroll_ms: Option<u32>,
+    /// Idle time after which a warm mic is released, in seconds (default 30).
+    pub warm_idle_secs: Option<f32>,
+    /// Processing applied to recordings before upload.
+    #[serde(default)]
+    pub dsp: DspConfig,
+}
+
+/// `audio.device`: a single device pattern or a preference list.
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+#[serde(untagged)]
+pub enum DeviceList {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl DeviceList {
+    pub fn to_vec(&self) -> Vec<String> {
+        match self {
+            DeviceList::One(device) => vec![device.clone()],
+            DeviceList::Many(devices) => devices.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Default)]
+pub struct DspConfig {
+    /// High-pass cutoff in Hz (default 80; 0 disables).
+    pub high_pass_hz: Option<f32>,
+    /// Level in dBFS below which audio is muted (default: no gate).
+    pub noise_gate_db: Option<f32>,
+    /// Normalization: "peak" (default), "loudness" or "off".
+    pub normalize: Option<Normalize>,
+    /// Normalization target in dBFS (default -1 for peak, -20 for loudness).
+    pub target_db: Option<f32>,
+    /// Most gain normalization may apply, in dB (default 20).
+    pub max_gain_db: Option<f32>,
 }
 
 #[derive(Debug, Clone, Deserialize, Default)]
@@ -72,22 +195,101 @@ provider = "groq"
 api_key_env = "GROQ_API_KEY"
 model = "whisper-large-v3-turbo"
 
+[transcription]
+max_attempts = 5
+retry_deadline_secs = 60.0
+connect_timeout_secs = 5.0
+timeout_secs = 90.0
+translate = true
+
 [audio]
 device = "default"
 sample_rate = 16000
+upload_codec = "flac"
+
+[audio.dsp]
+high_pass_hz = 100
+normalize = "loudness"
 
 [output]
 target = "stdout"
 "#;
         let cfg = FileConfig::from_toml(toml).expect("TOML should parse");
+        assert_eq!(cfg.transcription.max_attempts, Some(5));
+        assert_eq!(cfg.transcription.retry_deadline_secs, Some(60.0));
+        assert_eq!(cfg.transcription.connect_timeout_secs, Some(5.0));
+        assert_eq!(cfg.transcription.timeout_secs, Some(90.0));
+        assert_eq!(cfg.transcription.translate, Some(true));
         assert_eq!(cfg.provider.as_deref(), Some("groq"));
         let groq = cfg.providers.groq.expect("groq config should be present");
         assert_eq!(groq.api_key_env.as_deref(), Some("GROQ_API_KEY"));
         assert_eq!(groq.model.as_deref(), Some("whisper-large-v3-turbo"));
         assert_eq!(cfg.audio.sample_rate, Some(16000));
+        assert_eq!(cfg.audio.upload_codec, Some(UploadCodec::Flac));
+        assert_eq!(cfg.audio.dsp.high_pass_hz, Some(100.0));
+        assert_eq!(cfg.audio.dsp.normalize, Some(Normalize::Loudness));
+        assert!(cfg.audio.dsp.noise_gate_db.is_none());
         assert_eq!(cfg.output.target.as_deref(), Some("stdout"));
     }
 
+    #[test]
+    fn openai_section_parses() {
+        let toml = r#"
+provider = "openai"
+
+[providers.openai]
+base_url = "http://localhost:8000/v1"
+auth_header = "api-key"
+"#;
+        let cfg = FileConfig::from_toml(toml).expect("TOML should parse");
+        let openai = cfg
+            .providers
+            .openai
+            .expect("openai config should be present");
+        assert_eq!(openai.base_url.as_deref(), Some("http://localhost:8000/v1"));
+        assert_eq!(openai.auth_header.as_deref(), Some("api-key"));
+        assert!(openai.model.is_none());
+    }
+
+    #[test]
+    fn fallback_list_parses_in_order() {
+        let toml = r#"
+provider = "groq"
+fallback = ["openai", "groq"]
+"#;
+        let cfg = FileConfig::from_toml(toml).expect("TOML should parse");
+        assert_eq!(
+            cfg.fallback.as_deref(),
+            Some(&["openai".to_string(), "groq".to_string()][..])
+        );
+    }
+
+    #[test]
+    fn device_accepts_one_name_or_a_list() {
+        let one = FileConfig::from_toml("[audio]\ndevice = \"USB Audio\"").unwrap();
+        assert_eq!(
+            one.audio.device.unwrap().to_vec(),
+            vec!["USB Audio".to_string()]
+        );
+
+        let many = FileConfig::from_toml("[audio]\ndevice = [\"jabra\", \"/^USB/\"]").unwrap();
+        assert_eq!(
+            many.audio.device.unwrap().to_vec(),
+            vec!["jabra".to_string(), "/^USB/".to_string()]
+        );
+    }
+
+    #[test]
+    fn channel_accepts_an_index_or_mix() {
+        let index = FileConfig::from_toml("[audio]\nchannel = 2").unwrap();
+        assert_eq!(index.audio.channel, Some(ChannelSelect::Index(2)));
+
+        let mix = FileConfig::from_toml("[audio]\nchannel = \"mix\"").unwrap();
+        assert_eq!(mix.audio.channel, Some(ChannelSelect::Mix));
+
+        assert!(FileConfig::from_toml("[audio]\nchannel = \"left\"").is_err());
+    }
+
     #[test]
     fn partial_toml_works() {
         let toml = r#"provider = "groq""#;
@@ -18,10 +18,43 @@ pub enum VoxputError {
     #[diagnostic(code(voxput::provider))]
     Provider(String),
 
+    #[error("Transcription provider error: HTTP {status}{hint}: {message}")]
+    #[diagnostic(code(voxput::provider_http))]
+    ProviderHttp {
+        status: reqwest::StatusCode,
+        hint: &'static str,
+        message: String,
+    },
+
+    #[error("Provider '{provider}' does not support translation")]
+    #[diagnostic(
+        code(voxput::translation_unsupported),
+        help("Translation needs a provider with an /audio/translations endpoint, such as groq or openai")
+    )]
+    TranslationUnsupported { provider: String },
+
+    #[error("No speech detected in the recording")]
+    #[diagnostic(
+        code(voxput::no_speech),
+        help("Check the microphone input level, or lower audio.vad_threshold_db")
+    )]
+    NoSpeech,
+
+    #[error("Transcription cancelled")]
+    #[diagnostic(code(voxput::cancelled))]
+    Cancelled,
+
     #[error("API key not found: set {env_var} or add to ~/.config/voxput/config.toml")]
     #[diagnostic(code(voxput::missing_api_key))]
     MissingApiKey { env_var: String },
 
+    #[error("Unknown transcription provider '{name}' (expected one of: {valid})")]
+    #[diagnostic(
+        code(voxput::unknown_provider),
+        help("Set `provider` in ~/.config/voxput/config.toml to one of: {valid}")
+    )]
+    UnknownProvider { name: String, valid: String },
+
     #[error("Configuration error: {0}")]
     #[diagnostic(code(voxput::config))]
     Config(String),
@@ -40,4 +73,19 @@ pub enum VoxputError {
     Json(#[from] serde_json::Error),
 }
 
+impl VoxputError {
+    /// Whether the failure is transient, so the request may succeed if
+    /// retried or sent to another provider (rate limits, server errors,
+    /// timeouts and connection failures).
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::ProviderHttp { status, .. } => {
+                status.as_u16() == 408 || status.as_u16() == 429 || status.is_server_error()
+            }
+            Self::Http(e) => e.is_timeout() || e.is_connect() || e.is_request(),
+            _ => false,
+        }
+    }
+}
+
 pub type Result<T> = std::result::Result<T, VoxputError>;
@@ -0,0 +1,317 @@
+use crate::audio::trim::{contains_speech, TrimOptions};
+use crate::audio::vad::level_db;
+use crate::audio::AudioData;
+use crate::errors::{Result, VoxputError};
+use crate::provider::Transcript;
+
+/// Segments scoring below this average log-probability are only trusted if
+/// their `no_speech_prob` is low, matching Whisper's own silence rule.
+const LOW_CONFIDENCE_LOGPROB: f64 = -1.0;
+
+/// How far above `threshold_db` the loudest stretch of a recording must be
+/// for its speech to count as clear, so blocklisted phrases in it are kept.
+const CLEAR_SPEECH_MARGIN_DB: f32 = 15.0;
+
+/// Length of the stretches compared with the clear-speech level; long
+/// enough that a click or pop does not count.
+const LOUDNESS_WINDOW_SECS: f32 = 0.1;
+
+/// Phrases Whisper commonly produces for silent or near-silent audio.
+pub const DEFAULT_BLOCKLIST: &[&str] = &[
+    "thank you",
+    "thank you very much",
+    "thanks for watching",
+    "thank you for watching",
+    "please subscribe",
+    "subtitles by the amara org community",
+];
+
+/// Settings for [`check_transcript`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct GuardOptions {
+    /// Level in dBFS that always counts as speech when measuring the audio
+    /// (the same threshold silence trimming uses).
+    pub threshold_db: f32,
+    /// Verbose segments with a `no_speech_prob` at or above this, and low
+    /// confidence, are dropped.
+    pub no_speech_threshold: f64,
+    /// Whole transcripts dropped as hallucinations, compared ignoring case
+    /// and punctuation, when the audio is weak or the transcript unsure.
+    pub blocklist: Vec<String>,
+}
+
+impl Default for GuardOptions {
+    fn default() -> Self {
+        Self {
+            threshold_db: TrimOptions::default().threshold_db,
+            no_speech_threshold: 0.6,
+            blocklist: DEFAULT_BLOCKLIST.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+}
+
+/// Drop transcripts that Whisper made up for audio without speech.
+///
+/// Fails with [`VoxputError::NoSpeech`] when the audio holds no speech by
+/// signal energy, when every verbose segment is flagged as non-speech, or
+/// when what is left is empty. A blocklisted phrase is only dropped when
+/// the audio is weak (never `CLEAR_SPEECH_MARGIN_DB` above the speech
+/// threshold) or a remaining segment is low-confidence, so a clearly
+/// dictated "Thank you." survives. Otherwise returns the transcript minus
+/// any non-speech segments (and their words).
+pub fn check_transcript(
+    audio: &AudioData,
+    mut transcript: Transcript,
+    opts: &GuardOptions,
+) -> Result<Transcript> {
+    let speech = TrimOptions {
+        threshold_db: opts.threshold_db,
+        ..TrimOptions::default()
+    };
+    if !contains_speech(audio, &speech) {
+        tracing::info!(text = %transcript.text, "Dropping transcript of silent audio");
+        return Err(VoxputError::NoSpeech);
+    }
+
+    let (kept, dropped): (Vec<_>, Vec<_>) = transcript.segments.drain(..).partition(|s| {
+        let no_speech = s
+            .no_speech_prob
+            .is_some_and(|p| p >= opts.no_speech_threshold);
+        let unsure = s.avg_logprob.is_none_or(|lp| lp < LOW_CONFIDENCE_LOGPROB);
+        !(no_speech && unsure)
+    });
+    transcript.segments = kept;
+    if !dropped.is_empty() {
+        tracing::info!(dropped = dropped.len(), "Dropping non-speech segments");
+        transcript
+            .words
+            .retain(|w| !dropped.iter().any(|s| w.start >= s.start && w.end <= s.end));
+        transcript.text = transcript
+            .segments
+            .iter()
+            .map(|s| s.text.trim())
+            .collect::<Vec<_>>()
+            .join(" ");
+    }
+
+    let text = normalize(&transcript.text);
+    if text.is_empty() {
+        tracing::info!("Dropping empty transcript");
+        return Err(VoxputError::NoSpeech);
+    }
+    let doubtful = is_weak(audio, opts)
+        || transcript.segments.iter().any(|s| {
+            s.no_speech_prob
+                .is_some_and(|p| p >= opts.no_speech_threshold)
+                || s.avg_logprob.is_some_and(|lp| lp < LOW_CONFIDENCE_LOGPROB)
+        });
+    if doubtful
+        && opts
+            .blocklist
+            .iter()
+            .any(|phrase| normalize(phrase) == text)
+    {
+        tracing::info!(text = %transcript.text, "Dropping likely hallucination");
+        return Err(VoxputError::NoSpeech);
+    }
+    Ok(transcript)
+}
+
+/// Whether no stretch of `audio` is loud enough to count as clear speech.
+fn is_weak(audio: &AudioData, opts: &GuardOptions) -> bool {
+    let window = ((audio.sample_rate as f32 * LOUDNESS_WINDOW_SECS) as usize).max(1);
+    let loudest = audio
+        .samples
+        .chunks(window)
+        .map(level_db)
+        .fold(f32::NEG_INFINITY, f32::max);
+    loudest < opts.threshold_db + CLEAR_SPEECH_MARGIN_DB
+}
+
+/// Lower-case `text` and reduce it to words separated by single spaces.
+fn normalize(text: &str) -> String {
+    text.split(|c: char| !c.is_alphanumeric() && c != '\'')
+        .filter(|w| !w.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::{Segment, Word};
+    use std::f32::consts::TAU;
+
+    /// Half a second of silence either side of a second of tone at `amplitude`.
+    fn tone(amplitude: f32) -> AudioData {
+        let mut samples = vec![0.0; 8000];
+        samples.extend((0..16000).map(|i| (TAU * 220.0 * i as f32 / 16000.0).sin() * amplitude));
+        samples.extend(vec![0.0; 8000]);
+        AudioData {
+            samples,
+            sample_rate: 16000,
+            channels: 1,
+        }
+    }
+
+    /// Clearly audible speech, about -13 dBFS.
+    fn speech() -> AudioData {
+        tone(0.3)
+    }
+
+    /// Speech barely above the threshold, about -33 dBFS.
+    fn faint_speech() -> AudioData {
+        tone(0.03)
+    }
+
+    fn silence() -> AudioData {
+        AudioData {
+            samples: vec![0.0; 32000],
+            sample_rate: 16000,
+            channels: 1,
+        }
+    }
+
+    fn text(text: &str) -> Transcript {
+        Transcript {
+            text: text.into(),
+            ..Default::default()
+        }
+    }
+
+    fn segment(start: f64, end: f64, text: &str, no_speech_prob: f64, avg_logprob: f64) -> Segment {
+        Segment {
+            start,
+            end,
+            text: text.into(),
+            avg_logprob: Some(avg_logprob),
+            no_speech_prob: Some(no_speech_prob),
+        }
+    }
+
+    fn is_no_speech(result: Result<Transcript>) -> bool {
+        matches!(result, Err(VoxputError::NoSpeech))
+    }
+
+    #[test]
+    fn speech_passes_through() {
+        let out =
+            check_transcript(&speech(), text("Hello there."), &GuardOptions::default()).unwrap();
+        assert_eq!(out.text, "Hello there.");
+    }
+
+    #[test]
+    fn silent_audio_is_rejected_whatever_the_text() {
+        let result = check_transcript(&silence(), text("Hello there."), &GuardOptions::default());
+        assert!(is_no_speech(result));
+    }
+
+    #[test]
+    fn blocklisted_phrases_in_faint_audio_are_rejected_ignoring_case_and_punctuation() {
+        for phrase in ["Thank you.", " thanks for watching!", "..."] {
+            let result = check_transcript(&faint_speech(), text(phrase), &GuardOptions::default());
+            assert!(is_no_speech(result), "{phrase:?} should be dropped");
+        }
+        // Only whole-transcript matches count.
+        let out = check_transcript(
+            &faint_speech(),
+            text("Thank you for the update."),
+            &GuardOptions::default(),
+        );
+        assert!(out.is_ok());
+    }
+
+    #[test]
+    fn clearly_spoken_blocklisted_phrases_survive() {
+        let out =
+            check_transcript(&speech(), text("Thank you."), &GuardOptions::default()).unwrap();
+        assert_eq!(out.text, "Thank you.");
+        for phrase in ["You", "Bye!"] {
+            assert!(
+                check_transcript(&faint_speech(), text(phrase), &GuardOptions::default()).is_ok()
+            );
+        }
+    }
+
+    #[test]
+    fn low_confidence_blocklisted_phrases_are_rejected_in_loud_audio() {
+        let transcript = Transcript {
+            text: "Thank you.".into(),
+            segments: vec![segment(0.0, 1.0, "Thank you.", 0.3, -1.3)],
+            ..Default::default()
+        };
+        assert!(is_no_speech(check_transcript(
+            &speech(),
+            transcript,
+            &GuardOptions::default()
+        )));
+    }
+
+    #[test]
+    fn blocklist_is_configurable() {
+        let opts = GuardOptions {
+            blocklist: vec!["Ciao!".into()],
+            ..GuardOptions::default()
+        };
+        assert!(is_no_speech(check_transcript(
+            &faint_speech(),
+            text("ciao"),
+            &opts
+        )));
+        assert!(check_transcript(&faint_speech(), text("Thank you."), &opts).is_ok());
+    }
+
+    #[test]
+    fn non_speech_segments_and_their_words_are_dropped() {
+        let transcript = Transcript {
+            text: "Send the report. Thank you.".into(),
+            segments: vec![
+                segment(0.0, 1.0, " Send the report.", 0.05, -0.2),
+                segment(1.0, 2.0, " Thank you.", 0.9, -1.4),
+            ],
+            words: vec![
+                Word {
+                    word: "report".into(),
+                    start: 0.5,
+                    end: 0.9,
+                },
+                Word {
+                    word: "Thank".into(),
+                    start: 1.2,
+                    end: 1.4,
+                },
+            ],
+            ..Default::default()
+        };
+        let out = check_transcript(&speech(), transcript, &GuardOptions::default()).unwrap();
+        assert_eq!(out.text, "Send the report.");
+        assert_eq!(out.segments.len(), 1);
+        assert_eq!(out.words.len(), 1);
+    }
+
+    #[test]
+    fn confident_segments_are_kept_despite_high_no_speech_prob() {
+        let transcript = Transcript {
+            text: "Quiet words.".into(),
+            segments: vec![segment(0.0, 1.0, "Quiet words.", 0.8, -0.3)],
+            ..Default::default()
+        };
+        let out = check_transcript(&speech(), transcript, &GuardOptions::default()).unwrap();
+        assert_eq!(out.text, "Quiet words.");
+    }
+
+    #[test]
+    fn all_segments_non_speech_is_rejected() {
+        let transcript = Transcript {
+            text: "Hmm.".into(),
+            segments: vec![segment(0.0, 1.0, "Hmm.", 0.95, -1.2)],
+            ..Default::default()
+        };
+        assert!(is_no_speech(check_transcript(
+            &speech(),
+            transcript,
+            &GuardOptions::default()
+        )));
+    }
+}
@@ -1,6 +1,8 @@
 pub mod audio;
 pub mod config;
 pub mod errors;
+pub mod guard;
 pub mod output;
+pub mod pipeline;
 pub mod provider;
 pub mod state;
@@ -1,7 +1,9 @@
 pub mod clipboard;
 pub mod stdout;
+pub mod subtitle;
 
 use crate::errors::Result;
+use crate::provider::Transcript;
 use clap::ValueEnum;
 use serde::{Deserialize, Serialize};
 
@@ -14,6 +16,42 @@ pub enum OutputTarget {
     Both,
 }
 
+/// How a transcript is rendered before being written to a sink.
+#[derive(Debug, Clone, Copy, ValueEnum, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    /// Plain transcript text.
+    #[default]
+    Text,
+    /// The full `Transcript` as pretty-printed JSON.
+    Json,
+    /// SubRip subtitles.
+    Srt,
+    /// WebVTT subtitles.
+    Vtt,
+}
+
+impl OutputFormat {
+    /// Whether this format needs segment timestamps from the provider.
+    pub fn needs_timestamps(self) -> bool {
+        !matches!(self, OutputFormat::Text)
+    }
+}
+
+/// Render `transcript` in the given format.
+pub fn format_transcript(
+    transcript: &Transcript,
+    format: OutputFormat,
+    cues: &subtitle::CueOptions,
+) -> Result<String> {
+    Ok(match format {
+        OutputFormat::Text => transcript.text.clone(),
+        OutputFormat::Json => serde_json::to_string_pretty(transcript)?,
+        OutputFormat::Srt => subtitle::render_srt(transcript, cues),
+        OutputFormat::Vtt => subtitle::render_vtt(transcript, cues),
+    })
+}
+
 pub trait OutputSink: Send + Sync {
     fn write(&self, text: &str) -> Result<()>;
 }
@@ -40,6 +78,27 @@ impl OutputSink for BothSink {
 mod tests {
     use super::*;
 
+    #[test]
+    fn text_format_is_plain_transcript() {
+        let t = Transcript {
+            text: "hello".into(),
+            ..Default::default()
+        };
+        let out = format_transcript(&t, OutputFormat::Text, &Default::default()).unwrap();
+        assert_eq!(out, "hello");
+    }
+
+    #[test]
+    fn json_format_round_trips() {
+        let t = Transcript {
+            text: "hello".into(),
+            ..Default::default()
+        };
+        let out = format_transcript(&t, OutputFormat::Json, &Default::default()).unwrap();
+        let back: Transcript = serde_json::from_str(&out).unwrap();
+        assert_eq!(back.text, "hello");
+    }
+
     #[test]
     fn stdout_sink_does_not_error() {
         stdout::StdoutSink.write("test").expect("stdout sink should not error");
@@ -0,0 +1,286 @@
+use crate::provider::{Transcript, Word};
+
+/// Limits applied when splitting a transcript into subtitle cues.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CueOptions {
+    /// Maximum characters per subtitle line.
+    pub max_line_chars: usize,
+    /// Maximum lines per cue.
+    pub max_lines: usize,
+    /// Maximum cue duration in seconds.
+    pub max_duration_secs: f64,
+}
+
+impl Default for CueOptions {
+    fn default() -> Self {
+        Self {
+            max_line_chars: 42,
+            max_lines: 2,
+            max_duration_secs: 6.0,
+        }
+    }
+}
+
+/// A single subtitle cue.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cue {
+    /// Start time in seconds.
+    pub start: f64,
+    /// End time in seconds.
+    pub end: f64,
+    pub lines: Vec<String>,
+}
+
+/// Render a transcript as SubRip (`.srt`).
+pub fn render_srt(transcript: &Transcript, opts: &CueOptions) -> String {
+    let mut out = String::new();
+    for (i, cue) in build_cues(transcript, opts).iter().enumerate() {
+        out.push_str(&format!(
+            "{}\n{} --> {}\n{}\n\n",
+            i + 1,
+            timestamp(cue.start, ','),
+            timestamp(cue.end, ','),
+            cue.lines.join("\n")
+        ));
+    }
+    out
+}
+
+/// Render a transcript as WebVTT (`.vtt`).
+pub fn render_vtt(transcript: &Transcript, opts: &CueOptions) -> String {
+    let mut out = String::from("WEBVTT\n\n");
+    for cue in build_cues(transcript, opts) {
+        out.push_str(&format!(
+            "{} --> {}\n{}\n\n",
+            timestamp(cue.start, '.'),
+            timestamp(cue.end, '.'),
+            cue.lines.join("\n")
+        ));
+    }
+    out
+}
+
+/// Split a transcript into cues no longer than `opts` allows.
+///
+/// Uses word timestamps when the provider returned them; otherwise word
+/// times are interpolated across each segment in proportion to word length.
+/// A transcript without segments becomes a single span over its duration.
+pub fn build_cues(transcript: &Transcript, opts: &CueOptions) -> Vec<Cue> {
+    let words = timed_words(transcript);
+    let mut cues = Vec::new();
+    let mut current: Vec<&Word> = Vec::new();
+
+    for word in &words {
+        if let Some(first) = current.first() {
+            let mut candidate = current.clone();
+            candidate.push(word);
+            let too_long = wrap(&candidate, opts.max_line_chars).len() > opts.max_lines;
+            let too_slow = word.end - first.start > opts.max_duration_secs;
+            if too_long || too_slow {
+                cues.push(make_cue(&current, opts));
+                current.clear();
+            }
+        }
+        current.push(word);
+    }
+    if !current.is_empty() {
+        cues.push(make_cue(&current, opts));
+    }
+    cues
+}
+
+fn make_cue(words: &[&Word], opts: &CueOptions) -> Cue {
+    Cue {
+        start: words[0].start,
+        end: words[words.len() - 1].end,
+        lines: wrap(words, opts.max_line_chars),
+    }
+}
+
+/// Greedily wrap words into lines of at most `max_chars` characters.
+/// A single word longer than the limit gets a line of its own.
+fn wrap(words: &[&Word], max_chars: usize) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for word in words {
+        let text = word.word.trim();
+        match lines.last_mut() {
+            Some(line) if line.chars().count() + 1 + text.chars().count() <= max_chars => {
+                line.push(' ');
+                line.push_str(text);
+            }
+            _ => lines.push(text.to_string()),
+        }
+    }
+    lines
+}
+
+fn timed_words(transcript: &Transcript) -> Vec<Word> {
+    if !transcript.words.is_empty() {
+        return transcript
+            .words
+            .iter()
+            .filter(|w| !w.word.trim().is_empty())
+            .cloned()
+            .collect();
+    }
+
+    if transcript.segments.is_empty() {
+        let end = transcript.duration.unwrap_or(0.0);
+        return interpolate(&transcript.text, 0.0, end);
+    }
+
+    transcript
+        .segments
+        .iter()
+        .flat_map(|s| interpolate(&s.text, s.start, s.end))
+        .collect()
+}
+
+/// Spread the words of `text` across `start..end`, weighted by length.
+fn interpolate(text: &str, start: f64, end: f64) -> Vec<Word> {
+    let tokens: Vec<&str> = text.split_whitespace().collect();
+    let total: usize = tokens.iter().map(|t| t.chars().count()).sum();
+    let span = (end - start).max(0.0);
+
+    let mut t = start;
+    tokens
+        .into_iter()
+        .map(|tok| {
+            let share = if total == 0 {
+                0.0
+            } else {
+                span * tok.chars().count() as f64 / total as f64
+            };
+            let word = Word {
+                word: tok.to_string(),
+                start: t,
+                end: t + share,
+            };
+            t += share;
+            word
+        })
+        .collect()
+}
+
+/// Format seconds as `HH:MM:SS<sep>mmm`.
+fn timestamp(secs: f64, millis_sep: char) -> String {
+    let total_ms = (secs.max(0.0) * 1000.0).round() as u64;
+    let (h, rem) = (total_ms / 3_600_000, total_ms % 3_600_000);
+    let (m, rem) = (rem / 60_000, rem % 60_000);
+    let (s, ms) = (rem / 1000, rem % 1000);
+    format!("{h:02}:{m:02}:{s:02}{millis_sep}{ms:03}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::Segment;
+
+    fn word(w: &str, start: f64, end: f64) -> Word {
+        Word {
+            word: w.into(),
+            start,
+            end,
+        }
+    }
+
+    fn transcript_with_words(words: Vec<Word>) -> Transcript {
+        Transcript {
+            text: words
+                .iter()
+                .map(|w| w.word.as_str())
+                .collect::<Vec<_>>()
+                .join(" "),
+            words,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn timestamps_use_format_separator() {
+        assert_eq!(timestamp(0.0, ','), "00:00:00,000");
+        assert_eq!(timestamp(3723.456, ','), "01:02:03,456");
+        assert_eq!(timestamp(1.5, '.'), "00:00:01.500");
+    }
+
+    #[test]
+    fn srt_has_numbered_cues() {
+        let t = transcript_with_words(vec![word("Hello", 0.0, 0.5), word("world.", 0.6, 1.2)]);
+        assert_eq!(
+            render_srt(&t, &CueOptions::default()),
+            "1\n00:00:00,000 --> 00:00:01,200\nHello world.\n\n"
+        );
+    }
+
+    #[test]
+    fn vtt_has_header_and_dot_millis() {
+        let t = transcript_with_words(vec![word("Hello", 0.0, 0.5)]);
+        assert_eq!(
+            render_vtt(&t, &CueOptions::default()),
+            "WEBVTT\n\n00:00:00.000 --> 00:00:00.500\nHello\n\n"
+        );
+    }
+
+    #[test]
+    fn cues_respect_line_length_and_line_count() {
+        let words: Vec<Word> = (0..20)
+            .map(|i| word("abcdefghi", i as f64 * 0.1, i as f64 * 0.1 + 0.1))
+            .collect();
+        let opts = CueOptions {
+            max_line_chars: 20,
+            max_lines: 2,
+            max_duration_secs: 60.0,
+        };
+        let cues = build_cues(&transcript_with_words(words), &opts);
+
+        // Two 9-char words fit per 20-char line, two lines per cue.
+        assert_eq!(cues.len(), 5);
+        for cue in &cues {
+            assert!(cue.lines.len() <= 2);
+            assert!(cue.lines.iter().all(|l| l.chars().count() <= 20));
+        }
+    }
+
+    #[test]
+    fn cues_respect_max_duration() {
+        let words: Vec<Word> = (0..10)
+            .map(|i| word("hi", i as f64, i as f64 + 0.5))
+            .collect();
+        let opts = CueOptions {
+            max_duration_secs: 3.0,
+            ..CueOptions::default()
+        };
+        let cues = build_cues(&transcript_with_words(words), &opts);
+
+        assert!(cues.len() > 1);
+        for cue in &cues {
+            assert!(cue.end - cue.start <= 3.0, "cue too long: {cue:?}");
+        }
+    }
+
+    #[test]
+    fn segments_without_words_are_interpolated() {
+        let t = Transcript {
+            text: "one two".into(),
+            segments: vec![Segment {
+                start: 2.0,
+                end: 4.0,
+                text: " one two".into(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let cues = build_cues(&t, &CueOptions::default());
+        assert_eq!(cues.len(), 1);
+        assert_eq!(cues[0].start, 2.0);
+        assert!((cues[0].end - 4.0).abs() < 1e-9);
+        assert_eq!(cues[0].lines, vec!["one two".to_string()]);
+    }
+
+    #[test]
+    fn empty_transcript_renders_no_cues() {
+        let t = Transcript::default();
+        assert_eq!(render_srt(&t, &CueOptions::default()), "");
+        assert_eq!(render_vtt(&t, &CueOptions::default()), "WEBVTT\n\n");
+    }
+}
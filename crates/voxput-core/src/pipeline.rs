@@ -0,0 +1,454 @@
+use futures_util::{stream, StreamExt, TryStreamExt};
+use tokio::sync::mpsc::UnboundedReceiver;
+
+use crate::audio::chunk::{self, AudioChunk, ChunkOptions};
+use crate::audio::encode::UploadCodec;
+use crate::audio::AudioData;
+use crate::errors::{Result, VoxputError};
+use crate::provider::{Segment, TranscribeOptions, Transcript, TranscriptionProvider, Word};
+
+/// Assumed upper bound on speech rate, used to limit the overlap search.
+const MAX_WORDS_PER_SEC: f64 = 4.0;
+
+/// Fewest repeated words treated as overlap; a single shared word is too
+/// likely to be a coincidence ("the", "and") to drop.
+const MIN_OVERLAP_WORDS: usize = 2;
+
+/// Segments of an incremental recording transcribed at the same time.
+const SEGMENTS_IN_FLIGHT: usize = 3;
+
+/// Transcribe (or translate) `audio`, splitting it into chunks first when it
+/// is too long for a single request.
+///
+/// Chunks are sent up to `chunking.concurrency` at a time and stitched back
+/// together in order: segment and word timestamps are shifted to the full
+/// recording, and text repeated across the overlap between chunks is removed.
+pub async fn transcribe_audio(
+    provider: &dyn TranscriptionProvider,
+    audio: &AudioData,
+    translate: bool,
+    opts: &TranscribeOptions,
+    chunking: &ChunkOptions,
+    codec: UploadCodec,
+) -> Result<Transcript> {
+    let encoder = codec.encoder();
+    let chunks = chunk::split(audio, chunking);
+    if chunks.len() > 1 {
+        tracing::info!(
+            chunks = chunks.len(),
+            secs = audio.duration_secs(),
+            "Splitting long recording"
+        );
+    }
+
+    // Mapping indices rather than `&AudioChunk`s keeps the closure free of
+    // higher-ranked lifetimes, so callers can still `tokio::spawn` this future.
+    let parts: Vec<Transcript> = stream::iter(0..chunks.len())
+        .map(|i| {
+            let chunk = &chunks[i];
+            let encoder = &*encoder;
+            async move {
+                let encoded = encoder.encode(&chunk.audio)?;
+                tracing::debug!(
+                    offset = chunk.offset_secs,
+                    bytes = encoded.bytes.len(),
+                    file = encoded.file_name,
+                    "Uploading chunk"
+                );
+                if translate {
+                    provider.translate(&encoded, opts).await
+                } else {
+                    provider.transcribe(&encoded, opts).await
+                }
+            }
+        })
+        .buffered(chunking.concurrency.max(1))
+        .try_collect()
+        .await?;
+
+    let mut transcript = stitch(&chunks, parts);
+    if transcript.duration.is_some() {
+        transcript.duration = Some(audio.duration_secs() as f64);
+    }
+    Ok(transcript)
+}
+
+/// Transcribe (or translate) segments of a recording as they arrive, while
+/// it is still being captured.
+///
+/// Segments are sent up to three at a time as soon as they are received and
+/// appended to the transcript in recording order, however their responses
+/// come back. After each one, `on_partial` is called with the transcript so
+/// far. Returns once the sender is dropped and every segment is transcribed,
+/// or fails with [`VoxputError::NoSpeech`] if no segments were sent.
+pub async fn transcribe_segments(
+    provider: &dyn TranscriptionProvider,
+    segments: UnboundedReceiver<AudioChunk>,
+    translate: bool,
+    opts: &TranscribeOptions,
+    chunking: &ChunkOptions,
+    codec: UploadCodec,
+    mut on_partial: impl FnMut(&Transcript),
+) -> Result<Transcript> {
+    let arrivals = stream::unfold(segments, |mut rx| async move {
+        rx.recv().await.map(|s| (s, rx))
+    });
+    let parts = arrivals
+        .map(|segment| async move {
+            tracing::debug!(
+                offset = segment.offset_secs,
+                secs = segment.audio.duration_secs(),
+                "Transcribing segment"
+            );
+            let part = transcribe_audio(provider, &segment.audio, translate, opts, chunking, codec)
+                .await?;
+            Ok::<_, VoxputError>((segment, part))
+        })
+        .buffered(SEGMENTS_IN_FLIGHT);
+    let mut parts = std::pin::pin!(parts);
+
+    let mut merged = Transcript::default();
+    let mut end_secs = None;
+    while let Some(result) = parts.next().await {
+        let (segment, part) = result?;
+        end_secs = Some(segment.offset_secs + segment.audio.duration_secs() as f64);
+        append(&mut merged, &segment, part);
+        on_partial(&merged);
+    }
+
+    let Some(end_secs) = end_secs else {
+        return Err(VoxputError::NoSpeech);
+    };
+    if merged.duration.is_some() {
+        merged.duration = Some(end_secs);
+    }
+    Ok(merged)
+}
+
+/// Join per-chunk transcripts into one.
+fn stitch(chunks: &[AudioChunk], parts: Vec<Transcript>) -> Transcript {
+    let mut parts = chunks.iter().zip(parts);
+    let Some((_, mut merged)) = parts.next() else {
+        return Transcript::default();
+    };
+    for (chunk, part) in parts {
+        append(&mut merged, chunk, part);
+    }
+    merged
+}
+
+/// Add the transcript of `chunk` to `merged`, the transcript of everything
+/// before it.
+fn append(merged: &mut Transcript, chunk: &AudioChunk, part: Transcript) {
+    // Timed items in the overlap are kept from whichever chunk heard
+    // them furthest from its edge: split at the middle of the overlap.
+    let boundary = chunk.offset_secs + chunk.overlap_secs / 2.0;
+    let offset = chunk.offset_secs;
+
+    merged.segments.retain(|s| s.start < boundary);
+    merged.segments.extend(
+        part.segments
+            .into_iter()
+            .map(|s| Segment {
+                start: s.start + offset,
+                end: s.end + offset,
+                ..s
+            })
+            .filter(|s| s.start >= boundary),
+    );
+    merged.words.retain(|w| w.start < boundary);
+    merged.words.extend(
+        part.words
+            .into_iter()
+            .map(|w| Word {
+                start: w.start + offset,
+                end: w.end + offset,
+                ..w
+            })
+            .filter(|w| w.start >= boundary),
+    );
+
+    let max_overlap = (chunk.overlap_secs * MAX_WORDS_PER_SEC).ceil() as usize + 1;
+    merged.text = merge_text(&merged.text, &part.text, max_overlap);
+    merged.language = merged.language.take().or(part.language);
+    merged.duration = merged.duration.or(part.duration);
+    merged.provider = match (merged.provider.take(), part.provider) {
+        (Some(names), Some(name)) if !names.split(", ").any(|n| n == name) => {
+            Some(format!("{names}, {name}"))
+        }
+        (names, name) => names.or(name),
+    };
+}
+
+/// Append `next` to `prev`, dropping the longest run of up to `max_overlap`
+/// words that ends `prev` and also starts `next`. Words are compared
+/// ignoring case and punctuation.
+fn merge_text(prev: &str, next: &str, max_overlap: usize) -> String {
+    let prev_words: Vec<&str> = prev.split_whitespace().collect();
+    let next_words: Vec<&str> = next.split_whitespace().collect();
+    if prev_words.is_empty() {
+        return next_words.join(" ");
+    }
+
+    let longest = max_overlap.min(prev_words.len()).min(next_words.len());
+    let overlap = (MIN_OVERLAP_WORDS..=longest)
+        .rev()
+        .find(|&k| {
+            prev_words[prev_words.len() - k..]
+                .iter()
+                .zip(&next_words[..k])
+                .all(|(a, b)| normalize(a) == normalize(b))
+        })
+        .unwrap_or(0);
+
+    let mut text = prev.trim_end().to_string();
+    for word in &next_words[overlap..] {
+        text.push(' ');
+        text.push_str(word);
+    }
+    text
+}
+
+fn normalize(word: &str) -> String {
+    word.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::encode::EncodedAudio;
+    use async_trait::async_trait;
+    use std::sync::Mutex;
+
+    /// Returns one scripted transcript per call, in call order.
+    struct ScriptedProvider {
+        replies: Mutex<Vec<Transcript>>,
+    }
+
+    #[async_trait]
+    impl TranscriptionProvider for ScriptedProvider {
+        fn name(&self) -> &str {
+            "scripted"
+        }
+
+        async fn transcribe(&self, _: &EncodedAudio, _: &TranscribeOptions) -> Result<Transcript> {
+            Ok(self.replies.lock().unwrap().remove(0))
+        }
+    }
+
+    fn text(t: &str) -> Transcript {
+        Transcript {
+            text: t.into(),
+            ..Default::default()
+        }
+    }
+
+    fn chunk(offset_secs: f64, overlap_secs: f64) -> AudioChunk {
+        AudioChunk {
+            audio: AudioData {
+                samples: vec![],
+                sample_rate: 16000,
+                channels: 1,
+            },
+            offset_secs,
+            overlap_secs,
+        }
+    }
+
+    #[test]
+    fn merge_drops_repeated_words() {
+        assert_eq!(
+            merge_text("we should ship the release", "The release, on Friday.", 8),
+            "we should ship the release on Friday."
+        );
+    }
+
+    #[test]
+    fn merge_keeps_single_coincidental_word() {
+        assert_eq!(
+            merge_text("over to the", "the team", 8),
+            "over to the the team"
+        );
+    }
+
+    #[test]
+    fn merge_without_overlap_concatenates() {
+        assert_eq!(
+            merge_text("first part.", "Second part.", 8),
+            "first part. Second part."
+        );
+        assert_eq!(merge_text("", "only", 8), "only");
+    }
+
+    #[test]
+    fn stitch_shifts_and_dedupes_timed_items() {
+        let word = |w: &str, start: f64| Word {
+            word: w.into(),
+            start,
+            end: start + 0.4,
+        };
+        let first = Transcript {
+            words: vec![word("a", 8.0), word("b", 9.5)],
+            ..text("a b")
+        };
+        let second = Transcript {
+            // 0.5 s into a chunk starting at 9 s is inside the 2 s overlap.
+            words: vec![word("b", 0.5), word("c", 1.5)],
+            ..text("b c")
+        };
+        let merged = stitch(&[chunk(0.0, 0.0), chunk(9.0, 2.0)], vec![first, second]);
+
+        let words: Vec<_> = merged
+            .words
+            .iter()
+            .map(|w| (w.word.as_str(), w.start))
+            .collect();
+        assert_eq!(words, vec![("a", 8.0), ("b", 9.5), ("c", 10.5)]);
+    }
+
+    /// Replies with the length of the uploaded audio in samples, taking
+    /// longer for shorter audio so later segments can finish first.
+    struct SlowShortProvider;
+
+    #[async_trait]
+    impl TranscriptionProvider for SlowShortProvider {
+        fn name(&self) -> &str {
+            "slow-short"
+        }
+
+        async fn transcribe(
+            &self,
+            audio: &EncodedAudio,
+            _: &TranscribeOptions,
+        ) -> Result<Transcript> {
+            let samples = (audio.bytes.len() - 44) / 2;
+            tokio::time::sleep(std::time::Duration::from_millis(3000 / samples as u64)).await;
+            Ok(text(&format!("{samples}.")))
+        }
+    }
+
+    fn segment(offset_secs: f64, len: usize) -> AudioChunk {
+        AudioChunk {
+            audio: AudioData {
+                samples: vec![0.1; len],
+                sample_rate: 1000,
+                channels: 1,
+            },
+            offset_secs,
+            overlap_secs: 0.0,
+        }
+    }
+
+    #[tokio::test]
+    async fn segments_are_assembled_in_recording_order() {
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        for s in [segment(0.0, 10), segment(0.01, 30), segment(0.04, 300)] {
+            tx.send(s).unwrap();
+        }
+        drop(tx);
+
+        let mut partials = Vec::new();
+        let t = transcribe_segments(
+            &SlowShortProvider,
+            rx,
+            false,
+            &TranscribeOptions::default(),
+            &ChunkOptions::default(),
+            UploadCodec::Wav,
+            |t| partials.push(t.text.clone()),
+        )
+        .await
+        .unwrap();
+        assert_eq!(t.text, "10. 30. 300.");
+        assert_eq!(partials, vec!["10.", "10. 30.", "10. 30. 300."]);
+    }
+
+    #[tokio::test]
+    async fn no_segments_means_no_speech() {
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel::<AudioChunk>();
+        drop(tx);
+        let result = transcribe_segments(
+            &SlowShortProvider,
+            rx,
+            false,
+            &TranscribeOptions::default(),
+            &ChunkOptions::default(),
+            UploadCodec::Wav,
+            |_| {},
+        )
+        .await;
+        assert!(matches!(result, Err(VoxputError::NoSpeech)));
+    }
+
+    #[test]
+    fn segment_timestamps_are_shifted_to_the_recording() {
+        let timed = |t: &str| Transcript {
+            segments: vec![Segment {
+                start: 0.0,
+                end: 1.0,
+                text: t.into(),
+                ..Default::default()
+            }],
+            ..text(t)
+        };
+        let mut merged = Transcript::default();
+        append(&mut merged, &chunk(0.0, 0.0), timed("first"));
+        append(&mut merged, &chunk(4.5, 0.0), timed("second"));
+        let starts: Vec<f64> = merged.segments.iter().map(|s| s.start).collect();
+        assert_eq!(starts, vec![0.0, 4.5]);
+        assert_eq!(merged.text, "first second");
+    }
+
+    #[test]
+    fn providers_of_every_part_are_reported() {
+        let from = |provider: &str| Transcript {
+            provider: Some(provider.into()),
+            ..text("words")
+        };
+        let merged = stitch(
+            &[chunk(0.0, 0.0), chunk(9.0, 0.0), chunk(18.0, 0.0)],
+            vec![from("groq"), from("groq"), from("openai")],
+        );
+        assert_eq!(merged.provider.as_deref(), Some("groq, openai"));
+
+        let mut merged = Transcript::default();
+        append(&mut merged, &chunk(0.0, 0.0), from("groq"));
+        assert_eq!(merged.provider.as_deref(), Some("groq"));
+    }
+
+    #[tokio::test]
+    async fn long_audio_is_transcribed_in_order() {
+        let provider = ScriptedProvider {
+            replies: Mutex::new(vec![
+                text("one two three"),
+                text("two three four"),
+                text("five"),
+            ]),
+        };
+        let audio = AudioData {
+            samples: vec![0.1; 25_000],
+            sample_rate: 1000,
+            channels: 1,
+        };
+        let chunking = ChunkOptions {
+            max_chunk_secs: 10.0,
+            overlap_secs: 1.0,
+            concurrency: 2,
+        };
+
+        let t = transcribe_audio(
+            &provider,
+            &audio,
+            false,
+            &TranscribeOptions::default(),
+            &chunking,
+            UploadCodec::Wav,
+        )
+        .await
+        .unwrap();
+        assert_eq!(t.text, "one two three four five");
+    }
+}
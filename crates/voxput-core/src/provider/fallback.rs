@@ -0,0 +1,253 @@
+use async_trait::async_trait;
+
+use crate::audio::encode::EncodedAudio;
+use crate::errors::{Result, VoxputError};
+use crate::provider::{TranscribeOptions, Transcript, TranscriptionProvider};
+
+/// Tries an ordered list of providers, moving on to the next one when the
+/// current one fails with a retryable error (rate limit, 5xx, network).
+///
+/// Non-retryable errors (bad request, invalid key, ...) are returned as-is,
+/// since another provider would most likely reject the same audio.
+pub struct FallbackProvider {
+    providers: Vec<Box<dyn TranscriptionProvider>>,
+}
+
+impl FallbackProvider {
+    pub fn new(providers: Vec<Box<dyn TranscriptionProvider>>) -> Self {
+        Self { providers }
+    }
+
+    async fn run(
+        &self,
+        translate: bool,
+        audio: &EncodedAudio,
+        opts: &TranscribeOptions,
+    ) -> Result<Transcript> {
+        let mut last_err = None;
+
+        // Providers without a translations endpoint are skipped when translating.
+        let candidates = self
+            .providers
+            .iter()
+            .filter(|p| !translate || p.supports_translation());
+        for provider in candidates {
+            let result = if translate {
+                provider.translate(audio, opts).await
+            } else {
+                provider.transcribe(audio, opts).await
+            };
+            match result {
+                Ok(transcript) => return Ok(transcript),
+                Err(e) if e.is_retryable() => {
+                    tracing::warn!(
+                        provider = provider.name(),
+                        "Provider failed, trying next: {e}"
+                    );
+                    last_err = Some(e);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        Err(last_err.unwrap_or_else(|| {
+            VoxputError::Provider("no transcription providers configured".into())
+        }))
+    }
+}
+
+#[async_trait]
+impl TranscriptionProvider for FallbackProvider {
+    fn name(&self) -> &str {
+        "fallback"
+    }
+
+    async fn transcribe(
+        &self,
+        audio: &EncodedAudio,
+        opts: &TranscribeOptions,
+    ) -> Result<Transcript> {
+        self.run(false, audio, opts).await
+    }
+
+    fn supports_translation(&self) -> bool {
+        self.providers.iter().any(|p| p.supports_translation())
+    }
+
+    async fn translate(
+        &self,
+        audio: &EncodedAudio,
+        opts: &TranscribeOptions,
+    ) -> Result<Transcript> {
+        if !self.supports_translation() {
+            return Err(VoxputError::TranslationUnsupported {
+                provider: self.name().to_string(),
+            });
+        }
+        self.run(true, audio, opts).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    /// Test double returning a fixed HTTP status (or success) and counting calls.
+    struct StubProvider {
+        name: &'static str,
+        status: Option<u16>,
+        translates: bool,
+        calls: Arc<AtomicUsize>,
+    }
+
+    impl StubProvider {
+        fn boxed(
+            name: &'static str,
+            status: Option<u16>,
+        ) -> (Box<dyn TranscriptionProvider>, Arc<AtomicUsize>) {
+            let calls = Arc::new(AtomicUsize::new(0));
+            let stub = Self {
+                name,
+                status,
+                translates: false,
+                calls: Arc::clone(&calls),
+            };
+            (Box::new(stub), calls)
+        }
+
+        fn translating(name: &'static str) -> Box<dyn TranscriptionProvider> {
+            Box::new(Self {
+                name,
+                status: None,
+                translates: true,
+                calls: Arc::new(AtomicUsize::new(0)),
+            })
+        }
+    }
+
+    #[async_trait]
+    impl TranscriptionProvider for StubProvider {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        async fn transcribe(&self, _: &EncodedAudio, _: &TranscribeOptions) -> Result<Transcript> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            match self.status {
+                None => Ok(Transcript {
+                    text: format!("from {}", self.name),
+                    provider: Some(self.name.to_string()),
+                    ..Default::default()
+                }),
+                Some(code) => Err(VoxputError::ProviderHttp {
+                    status: reqwest::StatusCode::from_u16(code).unwrap(),
+                    hint: "",
+                    message: "stub failure".into(),
+                }),
+            }
+        }
+
+        fn supports_translation(&self) -> bool {
+            self.translates
+        }
+
+        async fn translate(
+            &self,
+            audio: &EncodedAudio,
+            opts: &TranscribeOptions,
+        ) -> Result<Transcript> {
+            let mut t = self.transcribe(audio, opts).await?;
+            t.text = format!("translated by {}", self.name);
+            Ok(t)
+        }
+    }
+
+    #[tokio::test]
+    async fn first_success_wins() {
+        let (a, a_calls) = StubProvider::boxed("a", None);
+        let (b, b_calls) = StubProvider::boxed("b", None);
+        let provider = FallbackProvider::new(vec![a, b]);
+
+        let t = provider
+            .transcribe(&EncodedAudio::wav(vec![]), &TranscribeOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(t.provider.as_deref(), Some("a"));
+        assert_eq!(a_calls.load(Ordering::SeqCst), 1);
+        assert_eq!(b_calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn rate_limit_falls_through_to_next_provider() {
+        let (a, _) = StubProvider::boxed("a", Some(429));
+        let (b, _) = StubProvider::boxed("b", Some(503));
+        let (c, _) = StubProvider::boxed("c", None);
+        let provider = FallbackProvider::new(vec![a, b, c]);
+
+        let t = provider
+            .transcribe(&EncodedAudio::wav(vec![]), &TranscribeOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(t.text, "from c");
+        assert_eq!(t.provider.as_deref(), Some("c"));
+    }
+
+    #[tokio::test]
+    async fn non_retryable_error_stops_the_chain() {
+        let (a, _) = StubProvider::boxed("a", Some(401));
+        let (b, b_calls) = StubProvider::boxed("b", None);
+        let provider = FallbackProvider::new(vec![a, b]);
+
+        let err = provider
+            .transcribe(&EncodedAudio::wav(vec![]), &TranscribeOptions::default())
+            .await
+            .expect_err("401 should not fall through");
+        assert!(err.to_string().contains("401"));
+        assert_eq!(b_calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn all_failing_returns_last_error() {
+        let (a, _) = StubProvider::boxed("a", Some(429));
+        let (b, _) = StubProvider::boxed("b", Some(502));
+        let provider = FallbackProvider::new(vec![a, b]);
+
+        let err = provider
+            .transcribe(&EncodedAudio::wav(vec![]), &TranscribeOptions::default())
+            .await
+            .expect_err("all providers failed");
+        assert!(
+            err.to_string().contains("502"),
+            "Expected last error, got: {err}"
+        );
+    }
+
+    #[tokio::test]
+    async fn translate_skips_providers_without_translation() {
+        let (a, a_calls) = StubProvider::boxed("a", None);
+        let provider = FallbackProvider::new(vec![a, StubProvider::translating("b")]);
+
+        assert!(provider.supports_translation());
+        let t = provider
+            .translate(&EncodedAudio::wav(vec![]), &TranscribeOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(t.text, "translated by b");
+        assert_eq!(a_calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn translate_unsupported_when_no_provider_translates() {
+        let (a, _) = StubProvider::boxed("a", None);
+        let provider = FallbackProvider::new(vec![a]);
+
+        assert!(!provider.supports_translation());
+        let err = provider
+            .translate(&EncodedAudio::wav(vec![]), &TranscribeOptions::default())
+            .await
+            .expect_err("no provider can translate");
+        assert!(matches!(err, VoxputError::TranslationUnsupported { .. }));
+    }
+}
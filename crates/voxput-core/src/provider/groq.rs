@@ -1,57 +1,115 @@
 use async_trait::async_trait;
-use reqwest::multipart;
-use serde::Deserialize;
+use std::time::Duration;
 
-use crate::errors::{Result, VoxputError};
-use crate::provider::{TranscribeOptions, Transcript, TranscriptionProvider};
+use crate::audio::encode::EncodedAudio;
+use crate::errors::Result;
+use crate::provider::http::{self, Task, TranscriptionResponse};
+use crate::provider::retry::RetryPolicy;
+use crate::provider::{
+    TranscribeOptions, Transcript, TranscriptionProvider, DEFAULT_CONNECT_TIMEOUT,
+    DEFAULT_REQUEST_TIMEOUT,
+};
 
-const GROQ_TRANSCRIPTION_URL: &str =
-    "https://api.groq.com/openai/v1/audio/transcriptions";
+const GROQ_BASE_URL: &str = "https://api.groq.com/openai/v1";
+
+const DEFAULT_MODEL: &str = "whisper-large-v3-turbo";
+
+/// Groq's turbo model cannot translate, so translations default to this one.
+const DEFAULT_TRANSLATION_MODEL: &str = "whisper-large-v3";
 
 pub struct GroqProvider {
     api_key: String,
     model: String,
+    translation_model: String,
     client: reqwest::Client,
     /// Override base URL for testing.
     base_url: String,
+    /// Where translations are posted.
+    translation_url: String,
+    retry: RetryPolicy,
 }
 
 impl GroqProvider {
     pub fn new(api_key: String, model: Option<String>) -> Self {
-        Self {
-            api_key,
-            model: model.unwrap_or_else(|| "whisper-large-v3-turbo".into()),
-            client: reqwest::Client::new(),
-            base_url: GROQ_TRANSCRIPTION_URL.to_string(),
-        }
+        Self::with_api_root(api_key, model, GROQ_BASE_URL.to_string())
     }
 
     /// Create with a custom base URL (for tests / mock servers).
+    ///
+    /// `base_url` is the full transcription endpoint. Translations go to the
+    /// sibling `translations` endpoint when it ends in `transcriptions`, and
+    /// to `base_url` itself otherwise.
     pub fn with_base_url(api_key: String, model: Option<String>, base_url: String) -> Self {
+        let translation_url = match base_url.strip_suffix("transcriptions") {
+            Some(prefix) => format!("{prefix}translations"),
+            None => base_url.clone(),
+        };
         Self {
             api_key,
-            model: model.unwrap_or_else(|| "whisper-large-v3-turbo".into()),
-            client: reqwest::Client::new(),
+            model: model.unwrap_or_else(|| DEFAULT_MODEL.into()),
+            translation_model: DEFAULT_TRANSLATION_MODEL.into(),
+            client: http::client(DEFAULT_CONNECT_TIMEOUT, DEFAULT_REQUEST_TIMEOUT),
             base_url,
+            translation_url,
+            retry: RetryPolicy::default(),
         }
     }
-}
 
-/// Groq error response body.
-#[derive(Debug, Deserialize)]
-struct GroqErrorBody {
-    error: GroqErrorDetail,
-}
+    /// Create against an OpenAI-style API root (e.g.
+    /// `https://api.groq.com/openai/v1`); endpoint paths such as
+    /// `/audio/transcriptions` are appended to it.
+    pub fn with_api_root(api_key: String, model: Option<String>, api_root: String) -> Self {
+        Self {
+            translation_url: http::endpoint(&api_root, Task::Translate),
+            ..Self::with_base_url(api_key, model, http::endpoint(&api_root, Task::Transcribe))
+        }
+    }
 
-#[derive(Debug, Deserialize)]
-struct GroqErrorDetail {
-    message: String,
-}
+    /// Translate with `model` instead of `whisper-large-v3`.
+    ///
+    /// The transcription model is not used for translations, since Groq's
+    /// turbo models cannot translate.
+    pub fn with_translation_model(mut self, model: Option<String>) -> Self {
+        if let Some(model) = model {
+            self.translation_model = model;
+        }
+        self
+    }
 
-/// Minimal response shape — Groq returns `{ "text": "..." }`.
-#[derive(Debug, Deserialize)]
-struct GroqResponse {
-    text: String,
+    /// Replace the default retry policy.
+    pub fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Replace the default connect and total request timeouts.
+    pub fn with_timeouts(mut self, connect: Duration, request: Duration) -> Self {
+        self.client = http::client(connect, request);
+        self
+    }
+
+    async fn request(
+        &self,
+        task: Task,
+        audio: &EncodedAudio,
+        opts: &TranscribeOptions,
+    ) -> Result<Transcript> {
+        let (model, url) = match task {
+            Task::Transcribe => (&self.model, &self.base_url),
+            Task::Translate => (&self.translation_model, &self.translation_url),
+        };
+        let resp = http::send_with_retry(&self.retry, &opts.cancel, || {
+            Ok(self
+                .client
+                .post(url)
+                .header("Authorization", format!("Bearer {}", self.api_key))
+                .multipart(http::request_form(task, audio, model, opts)?))
+        })
+        .await?;
+
+        let body: TranscriptionResponse = resp.json().await?;
+        Ok(body.into_transcript(self.name()))
+    }
 }
 
 #[async_trait]
@@ -60,61 +118,16 @@ impl TranscriptionProvider for GroqProvider {
         "groq"
     }
 
-    async fn transcribe(&self, audio_wav: &[u8], opts: &TranscribeOptions) -> Result<Transcript> {
-        let audio_part = multipart::Part::bytes(audio_wav.to_vec())
-            .file_name("audio.wav")
-            .mime_str("audio/wav")
-            .map_err(|e| VoxputError::Provider(format!("MIME error: {e}")))?;
-
-        let mut form = multipart::Form::new()
-            .part("file", audio_part)
-            .text("model", self.model.clone())
-            .text("response_format", "json");
-
-        if let Some(ref lang) = opts.language {
-            form = form.text("language", lang.clone());
-        }
-        if let Some(ref prompt) = opts.prompt {
-            form = form.text("prompt", prompt.clone());
-        }
-        if let Some(temp) = opts.temperature {
-            form = form.text("temperature", temp.to_string());
-        }
+    async fn transcribe(&self, audio: &EncodedAudio, opts: &TranscribeOptions) -> Result<Transcript> {
+        self.request(Task::Transcribe, audio, opts).await
+    }
 
-        let resp = self
-            .client
-            .post(&self.base_url)
-            .header("Authorization", format!("Bearer {}", self.api_key))
-            .multipart(form)
-            .send()
-            .await?;
-
-        let status = resp.status();
-
-        if !status.is_success() {
-            let hint = match status.as_u16() {
-                400 => " (audio too short — minimum 0.01 s; did you release the key too fast?)",
-                401 => " (invalid API key)",
-                413 => " (audio file too large; max 25 MB)",
-                429 => " (rate limited; wait and retry)",
-                _ => "",
-            };
-            // Try to extract API error message
-            let body = resp.text().await.unwrap_or_default();
-            let api_msg = serde_json::from_str::<GroqErrorBody>(&body)
-                .map(|e| e.error.message)
-                .unwrap_or_else(|_| body.clone());
-            return Err(VoxputError::Provider(format!(
-                "HTTP {status}{hint}: {api_msg}"
-            )));
-        }
+    fn supports_translation(&self) -> bool {
+        true
+    }
 
-        let groq_resp: GroqResponse = resp.json().await?;
-        Ok(Transcript {
-            text: groq_resp.text,
-            language: None,
-            duration: None,
-        })
+    async fn translate(&self, audio: &EncodedAudio, opts: &TranscribeOptions) -> Result<Transcript> {
+        self.request(Task::Translate, audio, opts).await
     }
 }
 
@@ -123,7 +136,7 @@ mod tests {
     use super::*;
     use mockito::ServerOpts;
 
-    fn dummy_wav() -> Vec<u8> {
+    fn dummy_wav() -> EncodedAudio {
         // Minimal valid WAV: 44-byte header + 0 data bytes
         let mut wav = Vec::new();
         wav.extend_from_slice(b"RIFF");
@@ -139,7 +152,7 @@ mod tests {
         wav.extend_from_slice(&16u16.to_le_bytes()); // bits per sample
         wav.extend_from_slice(b"data");
         wav.extend_from_slice(&0u32.to_le_bytes()); // data size
-        wav
+        EncodedAudio::wav(wav)
     }
 
     #[tokio::test]
@@ -167,6 +180,52 @@ mod tests {
         mock.assert_async().await;
     }
 
+    #[tokio::test]
+    async fn api_root_gets_the_transcriptions_path_appended() {
+        let mut server = mockito::Server::new_with_opts_async(ServerOpts::default()).await;
+        let mock = server
+            .mock("POST", "/openai/v1/audio/transcriptions")
+            .with_status(200)
+            .with_body(r#"{"text":"hello world"}"#)
+            .create_async()
+            .await;
+
+        let root = format!("{}/openai/v1/", server.url());
+        let provider = GroqProvider::with_api_root("test-key".into(), None, root);
+        let result = provider
+            .transcribe(&dummy_wav(), &TranscribeOptions::default())
+            .await
+            .expect("transcribe should succeed");
+
+        assert_eq!(result.text, "hello world");
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn base_url_is_the_full_transcription_endpoint() {
+        let mut server = mockito::Server::new_with_opts_async(ServerOpts::default()).await;
+        let transcribe = server
+            .mock("POST", "/v1/audio/transcriptions")
+            .with_status(200)
+            .with_body(r#"{"text":"hallo"}"#)
+            .create_async()
+            .await;
+        let translate = server
+            .mock("POST", "/v1/audio/translations")
+            .with_status(200)
+            .with_body(r#"{"text":"hello"}"#)
+            .create_async()
+            .await;
+
+        let url = format!("{}/v1/audio/transcriptions", server.url());
+        let provider = GroqProvider::with_base_url("test-key".into(), None, url);
+        let opts = TranscribeOptions::default();
+        assert_eq!(provider.transcribe(&dummy_wav(), &opts).await.unwrap().text, "hallo");
+        assert_eq!(provider.translate(&dummy_wav(), &opts).await.unwrap().text, "hello");
+        transcribe.assert_async().await;
+        translate.assert_async().await;
+    }
+
     #[tokio::test]
     async fn transcribe_400_audio_too_short() {
         let mut server = mockito::Server::new_with_opts_async(ServerOpts::default()).await;
@@ -216,6 +275,312 @@ mod tests {
         assert!(msg.contains("401"), "Expected 401 in: {msg}");
     }
 
+    fn fast_retry() -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: 3,
+            base_delay: std::time::Duration::from_millis(1),
+            max_delay: std::time::Duration::from_millis(5),
+            deadline: std::time::Duration::from_secs(5),
+        }
+    }
+
+    #[tokio::test]
+    async fn transcribe_retries_after_server_error() {
+        let mut server = mockito::Server::new_with_opts_async(ServerOpts::default()).await;
+        let failing = server
+            .mock("POST", "/audio/transcriptions")
+            .with_status(503)
+            .with_body(r#"{"error":{"message":"over capacity"}}"#)
+            .expect(2)
+            .create_async()
+            .await;
+        let ok = server
+            .mock("POST", "/audio/transcriptions")
+            .with_status(200)
+            .with_body(r#"{"text":"third time lucky"}"#)
+            .create_async()
+            .await;
+
+        let provider = GroqProvider::with_api_root("test-key".into(), None, server.url())
+            .with_retry_policy(fast_retry());
+        let result = provider
+            .transcribe(&dummy_wav(), &TranscribeOptions::default())
+            .await
+            .expect("should succeed after retries");
+
+        assert_eq!(result.text, "third time lucky");
+        failing.assert_async().await;
+        ok.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn transcribe_gives_up_after_max_attempts() {
+        let mut server = mockito::Server::new_with_opts_async(ServerOpts::default()).await;
+        let mock = server
+            .mock("POST", "/audio/transcriptions")
+            .with_status(500)
+            .expect(3)
+            .create_async()
+            .await;
+
+        let provider = GroqProvider::with_api_root("test-key".into(), None, server.url())
+            .with_retry_policy(fast_retry());
+        let err = provider
+            .transcribe(&dummy_wav(), &TranscribeOptions::default())
+            .await
+            .expect_err("should fail once attempts are exhausted");
+
+        assert!(err.to_string().contains("500"), "Expected 500 in: {err}");
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn transcribe_does_not_retry_client_errors() {
+        let mut server = mockito::Server::new_with_opts_async(ServerOpts::default()).await;
+        let mock = server
+            .mock("POST", "/audio/transcriptions")
+            .with_status(401)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let provider = GroqProvider::with_api_root("bad-key".into(), None, server.url())
+            .with_retry_policy(fast_retry());
+        provider
+            .transcribe(&dummy_wav(), &TranscribeOptions::default())
+            .await
+            .expect_err("should fail on 401");
+
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn transcribe_honours_retry_after() {
+        let mut server = mockito::Server::new_with_opts_async(ServerOpts::default()).await;
+        server
+            .mock("POST", "/audio/transcriptions")
+            .with_status(429)
+            .with_header("retry-after", "1")
+            .expect(1)
+            .create_async()
+            .await;
+        server
+            .mock("POST", "/audio/transcriptions")
+            .with_status(200)
+            .with_body(r#"{"text":"waited"}"#)
+            .create_async()
+            .await;
+
+        let provider = GroqProvider::with_api_root("test-key".into(), None, server.url())
+            .with_retry_policy(fast_retry());
+        let start = std::time::Instant::now();
+        let result = provider
+            .transcribe(&dummy_wav(), &TranscribeOptions::default())
+            .await
+            .expect("should succeed after Retry-After");
+
+        assert_eq!(result.text, "waited");
+        assert!(start.elapsed() >= std::time::Duration::from_secs(1));
+    }
+
+    #[tokio::test]
+    async fn transcribe_gives_up_when_retry_after_exceeds_deadline() {
+        let mut server = mockito::Server::new_with_opts_async(ServerOpts::default()).await;
+        let mock = server
+            .mock("POST", "/audio/transcriptions")
+            .with_status(429)
+            .with_header("retry-after", "120")
+            .expect(1)
+            .create_async()
+            .await;
+
+        let provider = GroqProvider::with_api_root("test-key".into(), None, server.url())
+            .with_retry_policy(fast_retry());
+        let err = provider
+            .transcribe(&dummy_wav(), &TranscribeOptions::default())
+            .await
+            .expect_err("should not wait past the deadline");
+
+        assert!(err.to_string().contains("429"), "Expected 429 in: {err}");
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn transcribe_times_out_on_hung_server() {
+        // A listener that accepts connections but never responds.
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let _hold = tokio::spawn(async move {
+            let mut conns = Vec::new();
+            while let Ok((conn, _)) = listener.accept().await {
+                conns.push(conn);
+            }
+        });
+
+        let provider = GroqProvider::with_api_root("test-key".into(), None, url)
+            .with_retry_policy(RetryPolicy::none())
+            .with_timeouts(Duration::from_secs(1), Duration::from_millis(200));
+        let err = provider
+            .transcribe(&dummy_wav(), &TranscribeOptions::default())
+            .await
+            .expect_err("should time out");
+
+        assert!(
+            matches!(err, crate::errors::VoxputError::Http(ref e) if e.is_timeout()),
+            "Expected timeout, got: {err}"
+        );
+    }
+
+    #[tokio::test]
+    async fn transcribe_aborts_when_cancelled() {
+        let mut server = mockito::Server::new_with_opts_async(ServerOpts::default()).await;
+        server
+            .mock("POST", "/audio/transcriptions")
+            .with_status(429)
+            .with_header("retry-after", "3")
+            .create_async()
+            .await;
+
+        let provider = GroqProvider::with_api_root("test-key".into(), None, server.url())
+            .with_retry_policy(fast_retry());
+        let opts = TranscribeOptions::default();
+        let cancel = opts.cancel.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            cancel.cancel();
+        });
+
+        let start = std::time::Instant::now();
+        let err = provider
+            .transcribe(&dummy_wav(), &opts)
+            .await
+            .expect_err("should be cancelled");
+
+        assert!(
+            matches!(err, crate::errors::VoxputError::Cancelled),
+            "got: {err}"
+        );
+        assert!(
+            start.elapsed() < Duration::from_secs(3),
+            "cancel should cut the backoff short"
+        );
+    }
+
+    #[tokio::test]
+    async fn transcribe_verbose_requests_timestamps_and_parses_segments() {
+        let mut server = mockito::Server::new_with_opts_async(ServerOpts::default()).await;
+        let mock = server
+            .mock("POST", "/audio/transcriptions")
+            .match_body(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::Regex("verbose_json".into()),
+                mockito::Matcher::Regex(r"timestamp_granularities\[\]".into()),
+            ]))
+            .with_status(200)
+            .with_body(
+                r#"{"text":"hi there","language":"english","duration":1.2,
+                    "segments":[{"start":0.0,"end":1.2,"text":"hi there",
+                                 "avg_logprob":-0.3,"no_speech_prob":0.02}],
+                    "words":[{"word":"hi","start":0.0,"end":0.4}]}"#,
+            )
+            .create_async()
+            .await;
+
+        let provider = GroqProvider::with_api_root("test-key".into(), None, server.url());
+        let opts = TranscribeOptions {
+            verbose: true,
+            ..Default::default()
+        };
+        let result = provider
+            .transcribe(&dummy_wav(), &opts)
+            .await
+            .expect("transcribe should succeed");
+
+        assert_eq!(result.language.as_deref(), Some("english"));
+        assert_eq!(result.duration, Some(1.2));
+        assert_eq!(result.segments.len(), 1);
+        assert_eq!(result.segments[0].no_speech_prob, Some(0.02));
+        assert_eq!(result.words.len(), 1);
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn translate_posts_to_translations_with_large_v3() {
+        let mut server = mockito::Server::new_with_opts_async(ServerOpts::default()).await;
+        let mock = server
+            .mock("POST", "/audio/translations")
+            .match_body(mockito::Matcher::Regex("whisper-large-v3\r\n".into()))
+            .with_status(200)
+            .with_body(r#"{"text":"good morning"}"#)
+            .create_async()
+            .await;
+
+        let provider = GroqProvider::with_api_root("test-key".into(), None, server.url());
+        assert!(provider.supports_translation());
+        let opts = TranscribeOptions {
+            language: Some("de".into()),
+            ..Default::default()
+        };
+        let result = provider
+            .translate(&dummy_wav(), &opts)
+            .await
+            .expect("translate should succeed");
+
+        assert_eq!(result.text, "good morning");
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn translate_ignores_a_turbo_model_override() {
+        let mut server = mockito::Server::new_with_opts_async(ServerOpts::default()).await;
+        let transcribe = server
+            .mock("POST", "/audio/transcriptions")
+            .match_body(mockito::Matcher::Regex("distil-whisper-large-v3-en\r\n".into()))
+            .with_status(200)
+            .with_body(r#"{"text":"guten Morgen"}"#)
+            .create_async()
+            .await;
+        let translate = server
+            .mock("POST", "/audio/translations")
+            .match_body(mockito::Matcher::Regex("name=\"model\"\r\n\r\nwhisper-large-v3\r\n".into()))
+            .with_status(200)
+            .with_body(r#"{"text":"good morning"}"#)
+            .create_async()
+            .await;
+
+        let provider = GroqProvider::with_api_root(
+            "test-key".into(),
+            Some("distil-whisper-large-v3-en".into()),
+            server.url(),
+        );
+        let opts = TranscribeOptions::default();
+        provider.transcribe(&dummy_wav(), &opts).await.unwrap();
+        let result = provider.translate(&dummy_wav(), &opts).await.unwrap();
+
+        assert_eq!(result.text, "good morning");
+        transcribe.assert_async().await;
+        translate.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn translation_model_can_be_configured() {
+        let mut server = mockito::Server::new_with_opts_async(ServerOpts::default()).await;
+        let mock = server
+            .mock("POST", "/audio/translations")
+            .match_body(mockito::Matcher::Regex("name=\"model\"\r\n\r\nwhisper-large-v2\r\n".into()))
+            .with_status(200)
+            .with_body(r#"{"text":"good morning"}"#)
+            .create_async()
+            .await;
+
+        let provider = GroqProvider::with_api_root("test-key".into(), None, server.url())
+            .with_translation_model(Some("whisper-large-v2".into()));
+        provider
+            .translate(&dummy_wav(), &TranscribeOptions::default())
+            .await
+            .unwrap();
+        mock.assert_async().await;
+    }
+
     #[tokio::test]
     async fn transcribe_with_language_option() {
         let mut server = mockito::Server::new_with_opts_async(ServerOpts::default()).await;
@@ -0,0 +1,281 @@
+use std::time::{Duration, Instant};
+
+use reqwest::multipart;
+use serde::Deserialize;
+
+use crate::audio::encode::EncodedAudio;
+use crate::errors::{Result, VoxputError};
+use crate::provider::retry::{parse_retry_after, RetryPolicy};
+use crate::provider::{Segment, TranscribeOptions, Transcript, Word};
+use tokio_util::sync::CancellationToken;
+
+/// OpenAI-style error response body (`{ "error": { "message": "..." } }`).
+#[derive(Debug, Deserialize)]
+struct ApiErrorBody {
+    error: ApiErrorDetail,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiErrorDetail {
+    message: String,
+}
+
+/// Response body for both `json` (`{ "text": "..." }`) and `verbose_json`
+/// (which adds language, duration, segments and words).
+#[derive(Debug, Deserialize)]
+pub(crate) struct TranscriptionResponse {
+    text: String,
+    #[serde(default)]
+    language: Option<String>,
+    #[serde(default)]
+    duration: Option<f64>,
+    #[serde(default)]
+    segments: Vec<Segment>,
+    #[serde(default)]
+    words: Vec<Word>,
+}
+
+impl TranscriptionResponse {
+    pub(crate) fn into_transcript(self, provider: &str) -> Transcript {
+        Transcript {
+            text: self.text,
+            language: self.language,
+            duration: self.duration,
+            provider: Some(provider.to_string()),
+            segments: self.segments,
+            words: self.words,
+        }
+    }
+}
+
+/// Build an HTTP client with the given connect and total request timeouts.
+pub(crate) fn client(connect_timeout: Duration, request_timeout: Duration) -> reqwest::Client {
+    reqwest::Client::builder()
+        .connect_timeout(connect_timeout)
+        .timeout(request_timeout)
+        .build()
+        .unwrap_or_else(|e| {
+            tracing::warn!("Failed to build HTTP client, using defaults: {e}");
+            reqwest::Client::new()
+        })
+}
+
+/// Which Whisper-style endpoint a request targets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Task {
+    /// `/audio/transcriptions`: text in the spoken language.
+    Transcribe,
+    /// `/audio/translations`: text translated into English.
+    Translate,
+}
+
+/// Endpoint URL for `task` under an OpenAI-style `base_url` (e.g. `.../v1`).
+pub(crate) fn endpoint(base_url: &str, task: Task) -> String {
+    let path = match task {
+        Task::Transcribe => "audio/transcriptions",
+        Task::Translate => "audio/translations",
+    };
+    format!("{}/{path}", base_url.trim_end_matches('/'))
+}
+
+/// Build the multipart form for a transcription or translation request.
+///
+/// Translations always target English, so the language hint and word
+/// timestamp granularities (unsupported by the endpoint) are omitted.
+pub(crate) fn request_form(
+    task: Task,
+    audio: &EncodedAudio,
+    model: &str,
+    opts: &TranscribeOptions,
+) -> Result<multipart::Form> {
+    let audio_part = multipart::Part::bytes(audio.bytes.clone())
+        .file_name(audio.file_name)
+        .mime_str(audio.mime_type)
+        .map_err(|e| VoxputError::Provider(format!("MIME error: {e}")))?;
+
+    let mut form = multipart::Form::new()
+        .part("file", audio_part)
+        .text("model", model.to_string());
+
+    if opts.verbose {
+        form = form.text("response_format", "verbose_json");
+        if task == Task::Transcribe {
+            form = form
+                .text("timestamp_granularities[]", "segment")
+                .text("timestamp_granularities[]", "word");
+        }
+    } else {
+        form = form.text("response_format", "json");
+    }
+
+    if let Some(ref lang) = opts.language {
+        if task == Task::Transcribe {
+            form = form.text("language", lang.clone());
+        }
+    }
+    if let Some(ref prompt) = opts.prompt {
+        form = form.text("prompt", prompt.clone());
+    }
+    if let Some(temp) = opts.temperature {
+        form = form.text("temperature", temp.to_string());
+    }
+
+    Ok(form)
+}
+
+/// User-facing hint appended to the error for well-known HTTP statuses.
+fn status_hint(status: u16) -> &'static str {
+    match status {
+        400 => " (audio too short — minimum 0.01 s; did you release the key too fast?)",
+        401 => " (invalid API key)",
+        413 => " (audio file too large; max 25 MB)",
+        429 => " (rate limited; wait and retry)",
+        _ => "",
+    }
+}
+
+/// Convert a non-success response into a `VoxputError::ProviderHttp`.
+async fn error_from_response(resp: reqwest::Response) -> VoxputError {
+    let status = resp.status();
+    // Try to extract API error message
+    let body = resp.text().await.unwrap_or_default();
+    let message = serde_json::from_str::<ApiErrorBody>(&body)
+        .map(|e| e.error.message)
+        .unwrap_or(body);
+    VoxputError::ProviderHttp {
+        status,
+        hint: status_hint(status.as_u16()),
+        message,
+    }
+}
+
+/// Send the request built by `build`, retrying per `policy`.
+///
+/// `build` is called once per attempt because a multipart body cannot be
+/// reused. Returns the first successful response, or the last error once
+/// the failure is not retryable or the policy is exhausted. Cancelling
+/// `cancel` aborts the request or backoff with `VoxputError::Cancelled`.
+pub(crate) async fn send_with_retry<F>(
+    policy: &RetryPolicy,
+    cancel: &CancellationToken,
+    mut build: F,
+) -> Result<reqwest::Response>
+where
+    F: FnMut() -> Result<reqwest::RequestBuilder>,
+{
+    let start = Instant::now();
+    let mut attempt = 1;
+
+    loop {
+        let sent = tokio::select! {
+            sent = build()?.send() => sent,
+            _ = cancel.cancelled() => return Err(VoxputError::Cancelled),
+        };
+        let (err, retry_after) = match sent {
+            Ok(resp) if resp.status().is_success() => return Ok(resp),
+            Ok(resp) => {
+                let retry_after = retry_after(&resp);
+                (error_from_response(resp).await, retry_after)
+            }
+            Err(e) => (VoxputError::from(e), None),
+        };
+
+        if attempt >= policy.max_attempts || !err.is_retryable() {
+            return Err(err);
+        }
+        let delay = retry_after.unwrap_or_else(|| policy.backoff(attempt));
+        if start.elapsed() + delay > policy.deadline {
+            tracing::debug!(?delay, "Retry would exceed deadline; giving up");
+            return Err(err);
+        }
+
+        tracing::warn!(
+            attempt,
+            ?delay,
+            "Transcription request failed, retrying: {err}"
+        );
+        tokio::select! {
+            _ = tokio::time::sleep(delay) => {}
+            _ = cancel.cancelled() => return Err(VoxputError::Cancelled),
+        }
+        attempt += 1;
+    }
+}
+
+/// `Retry-After` delay of a 429/503 response, if present.
+fn retry_after(resp: &reqwest::Response) -> Option<Duration> {
+    if !matches!(resp.status().as_u16(), 429 | 503) {
+        return None;
+    }
+    resp.headers()
+        .get(reqwest::header::RETRY_AFTER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(parse_retry_after)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plain_json_response_has_no_segments() {
+        let body: TranscriptionResponse = serde_json::from_str(r#"{"text":"hi"}"#).unwrap();
+        let t = body.into_transcript("groq");
+        assert_eq!(t.text, "hi");
+        assert_eq!(t.provider.as_deref(), Some("groq"));
+        assert!(t.segments.is_empty() && t.language.is_none());
+    }
+
+    #[test]
+    fn verbose_json_response_parses_segments_and_words() {
+        let body: TranscriptionResponse = serde_json::from_str(
+            r#"{
+                "task": "transcribe",
+                "language": "english",
+                "duration": 2.5,
+                "text": "Hello world.",
+                "segments": [{
+                    "id": 0, "seek": 0, "start": 0.0, "end": 2.5,
+                    "text": " Hello world.", "tokens": [1, 2],
+                    "temperature": 0.0, "avg_logprob": -0.25,
+                    "compression_ratio": 0.8, "no_speech_prob": 0.01
+                }],
+                "words": [
+                    {"word": "Hello", "start": 0.0, "end": 0.9},
+                    {"word": "world.", "start": 1.0, "end": 2.4}
+                ]
+            }"#,
+        )
+        .unwrap();
+        let t = body.into_transcript("openai");
+        assert_eq!(t.language.as_deref(), Some("english"));
+        assert_eq!(t.duration, Some(2.5));
+        assert_eq!(t.segments.len(), 1);
+        assert_eq!(t.segments[0].text, " Hello world.");
+        assert_eq!(t.segments[0].avg_logprob, Some(-0.25));
+        assert_eq!(t.segments[0].no_speech_prob, Some(0.01));
+        assert_eq!(t.words.len(), 2);
+        assert_eq!(t.words[1].word, "world.");
+    }
+
+    #[test]
+    fn endpoint_joins_task_path() {
+        assert_eq!(
+            endpoint("https://api.groq.com/openai/v1", Task::Transcribe),
+            "https://api.groq.com/openai/v1/audio/transcriptions"
+        );
+        assert_eq!(
+            endpoint("http://localhost:8000/v1/", Task::Translate),
+            "http://localhost:8000/v1/audio/translations"
+        );
+    }
+
+    #[test]
+    fn hint_for_known_statuses() {
+        assert!(status_hint(400).contains("too short"));
+        assert!(status_hint(401).contains("API key"));
+        assert!(status_hint(413).contains("too large"));
+        assert!(status_hint(429).contains("rate limited"));
+        assert_eq!(status_hint(500), "");
+    }
+}
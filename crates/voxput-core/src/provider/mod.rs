@@ -1,16 +1,66 @@
+pub mod fallback;
 pub mod groq;
+mod http;
+pub mod openai_compatible;
+pub mod retry;
 
-use crate::errors::Result;
+use crate::audio::encode::EncodedAudio;
+use crate::config::ResolvedConfig;
+use crate::errors::{Result, VoxputError};
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Default time allowed to establish a connection to a provider.
+pub const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Default time allowed for a whole request, from connect to reading the body.
+pub const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(120);
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Transcript {
     pub text: String,
     #[serde(default)]
     pub language: Option<String>,
     #[serde(default)]
     pub duration: Option<f64>,
+    /// Name of the provider that produced this transcript, or the names of
+    /// each, comma-separated, when its parts came from different providers.
+    #[serde(default)]
+    pub provider: Option<String>,
+    /// Timed segments (verbose mode only).
+    #[serde(default)]
+    pub segments: Vec<Segment>,
+    /// Word-level timestamps (verbose mode only).
+    #[serde(default)]
+    pub words: Vec<Word>,
+}
+
+/// A timed segment of a verbose transcript.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Segment {
+    /// Start time in seconds.
+    pub start: f64,
+    /// End time in seconds.
+    pub end: f64,
+    pub text: String,
+    /// Average token log-probability; lower means less confident.
+    #[serde(default)]
+    pub avg_logprob: Option<f64>,
+    /// Probability that the segment contains no speech.
+    #[serde(default)]
+    pub no_speech_prob: Option<f64>,
+}
+
+/// A single word with timestamps.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Word {
+    pub word: String,
+    /// Start time in seconds.
+    pub start: f64,
+    /// End time in seconds.
+    pub end: f64,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -18,10 +68,164 @@ pub struct TranscribeOptions {
     pub language: Option<String>,
     pub prompt: Option<String>,
     pub temperature: Option<f32>,
+    /// Request `verbose_json` with segment and word timestamps, filling
+    /// `Transcript::language`, `duration`, `segments` and `words`.
+    pub verbose: bool,
+    /// Cancelling this token aborts an in-flight request (including retry
+    /// backoff) with `VoxputError::Cancelled`.
+    pub cancel: CancellationToken,
 }
 
 #[async_trait]
 pub trait TranscriptionProvider: Send + Sync {
-    async fn transcribe(&self, audio_wav: &[u8], opts: &TranscribeOptions) -> Result<Transcript>;
+    async fn transcribe(
+        &self,
+        audio: &EncodedAudio,
+        opts: &TranscribeOptions,
+    ) -> Result<Transcript>;
     fn name(&self) -> &str;
+
+    /// Whether [`translate`](Self::translate) is available.
+    fn supports_translation(&self) -> bool {
+        false
+    }
+
+    /// Transcribe speech in any language and translate it into English.
+    /// `opts.language` is ignored. Fails with `TranslationUnsupported` unless
+    /// [`supports_translation`](Self::supports_translation) returns true.
+    async fn translate(
+        &self,
+        _audio: &EncodedAudio,
+        _opts: &TranscribeOptions,
+    ) -> Result<Transcript> {
+        Err(VoxputError::TranslationUnsupported {
+            provider: self.name().to_string(),
+        })
+    }
+}
+
+/// Provider names accepted by the `provider` config key.
+pub const PROVIDER_NAMES: &[&str] = &["groq", "openai"];
+
+/// Build the transcription provider selected by `config.provider`.
+///
+/// `config.model` (from `VOXPUT_MODEL` or `--model`) takes precedence over
+/// the model set in the provider's own config section. When `config.fallback`
+/// names further providers, the result is a [`fallback::FallbackProvider`]
+/// trying the selected provider first and then each fallback in order.
+pub fn create_provider(config: &ResolvedConfig) -> Result<Box<dyn TranscriptionProvider>> {
+    let primary = create_named_provider(&config.provider, config, config.model.clone())?;
+    if config.fallback.is_empty() {
+        return Ok(primary);
+    }
+
+    let mut chain = vec![primary];
+    for name in &config.fallback {
+        chain.push(create_named_provider(name, config, None)?);
+    }
+    Ok(Box::new(fallback::FallbackProvider::new(chain)))
+}
+
+fn create_named_provider(
+    name: &str,
+    config: &ResolvedConfig,
+    model_override: Option<String>,
+) -> Result<Box<dyn TranscriptionProvider>> {
+    let model = |own: &Option<String>| model_override.clone().or_else(|| own.clone());
+
+    match name {
+        "groq" => {
+            let settings = &config.groq;
+            Ok(Box::new(
+                groq::GroqProvider::new(settings.api_key()?, model(&settings.model))
+                    .with_translation_model(settings.translation_model.clone())
+                    .with_retry_policy(config.retry.clone())
+                    .with_timeouts(config.connect_timeout, config.request_timeout),
+            ))
+        }
+        "openai" => {
+            let settings = &config.openai;
+            let base_url = settings
+                .base_url
+                .clone()
+                .unwrap_or_else(|| openai_compatible::OPENAI_BASE_URL.to_string());
+            // Local servers commonly run without auth, so a missing key is not an error.
+            let mut provider = openai_compatible::OpenAiCompatibleProvider::new(
+                base_url,
+                settings.api_key().ok(),
+                model(&settings.model),
+            )
+            .with_retry_policy(config.retry.clone())
+            .with_timeouts(config.connect_timeout, config.request_timeout);
+            if let Some(ref header) = settings.auth_header {
+                provider = provider.with_auth_header(header.clone());
+            }
+            Ok(Box::new(provider))
+        }
+        other => Err(VoxputError::UnknownProvider {
+            name: other.to_string(),
+            valid: PROVIDER_NAMES.join(", "),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn creates_groq_provider_by_default() {
+        let mut config = ResolvedConfig::default();
+        config.groq.api_key = Some("gsk-test".into());
+        let provider = create_provider(&config).expect("groq provider should build");
+        assert_eq!(provider.name(), "groq");
+    }
+
+    #[test]
+    fn groq_without_key_is_an_error() {
+        let mut config = ResolvedConfig::default();
+        config.groq.api_key_env = "VOXPUT_TEST_MISSING_KEY_XYZ".into();
+        let err = create_provider(&config)
+            .err()
+            .expect("missing key should fail");
+        assert!(matches!(err, VoxputError::MissingApiKey { .. }));
+    }
+
+    #[test]
+    fn creates_openai_provider_without_key() {
+        let mut config = ResolvedConfig {
+            provider: "openai".into(),
+            ..ResolvedConfig::default()
+        };
+        config.openai.api_key_env = "VOXPUT_TEST_MISSING_KEY_XYZ".into();
+        config.openai.base_url = Some("http://localhost:8000/v1".into());
+        let provider = create_provider(&config).expect("openai provider should build");
+        assert_eq!(provider.name(), "openai");
+    }
+
+    #[test]
+    fn fallback_list_builds_fallback_chain() {
+        let mut config = ResolvedConfig {
+            fallback: vec!["openai".into()],
+            ..ResolvedConfig::default()
+        };
+        config.groq.api_key = Some("gsk-test".into());
+        let provider = create_provider(&config).expect("fallback chain should build");
+        assert_eq!(provider.name(), "fallback");
+    }
+
+    #[test]
+    fn unknown_provider_lists_valid_names() {
+        let config = ResolvedConfig {
+            provider: "nope".into(),
+            ..ResolvedConfig::default()
+        };
+        let err = create_provider(&config)
+            .err()
+            .expect("unknown provider should fail");
+        let msg = err.to_string();
+        for name in PROVIDER_NAMES {
+            assert!(msg.contains(name), "Expected {name} in: {msg}");
+        }
+    }
 }
@@ -0,0 +1,276 @@
+use async_trait::async_trait;
+use std::time::Duration;
+
+use crate::audio::encode::EncodedAudio;
+use crate::errors::Result;
+use crate::provider::http::{self, Task, TranscriptionResponse};
+use crate::provider::retry::RetryPolicy;
+use crate::provider::{
+    TranscribeOptions, Transcript, TranscriptionProvider, DEFAULT_CONNECT_TIMEOUT,
+    DEFAULT_REQUEST_TIMEOUT,
+};
+
+/// Base URL of the hosted OpenAI API.
+pub const OPENAI_BASE_URL: &str = "https://api.openai.com/v1";
+
+/// Default model for OpenAI-compatible endpoints.
+pub const DEFAULT_MODEL: &str = "whisper-1";
+
+/// Provider for any server exposing an OpenAI-style `/audio/transcriptions`
+/// endpoint (OpenAI, LocalAI, faster-whisper-server, ...).
+pub struct OpenAiCompatibleProvider {
+    /// Endpoint base URL, e.g. `http://localhost:8000/v1`.
+    base_url: String,
+    /// API key; `None` sends no auth header (common for local servers).
+    api_key: Option<String>,
+    /// Header carrying the API key.
+    auth_header: String,
+    model: String,
+    client: reqwest::Client,
+    retry: RetryPolicy,
+}
+
+impl OpenAiCompatibleProvider {
+    pub fn new(base_url: String, api_key: Option<String>, model: Option<String>) -> Self {
+        Self {
+            base_url,
+            api_key,
+            auth_header: "Authorization".into(),
+            model: model.unwrap_or_else(|| DEFAULT_MODEL.into()),
+            client: http::client(DEFAULT_CONNECT_TIMEOUT, DEFAULT_REQUEST_TIMEOUT),
+            retry: RetryPolicy::default(),
+        }
+    }
+
+    /// Send the API key in `header` instead of `Authorization`.
+    ///
+    /// The `Authorization` header carries `Bearer <key>`; any other header
+    /// (e.g. Azure's `api-key`) carries the bare key.
+    pub fn with_auth_header(mut self, header: String) -> Self {
+        self.auth_header = header;
+        self
+    }
+
+    /// Replace the default retry policy.
+    pub fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Replace the default connect and total request timeouts.
+    pub fn with_timeouts(mut self, connect: Duration, request: Duration) -> Self {
+        self.client = http::client(connect, request);
+        self
+    }
+
+    async fn request(
+        &self,
+        task: Task,
+        audio: &EncodedAudio,
+        opts: &TranscribeOptions,
+    ) -> Result<Transcript> {
+        let url = http::endpoint(&self.base_url, task);
+        let resp = http::send_with_retry(&self.retry, &opts.cancel, || {
+            let form = http::request_form(task, audio, &self.model, opts)?;
+            let mut req = self.client.post(&url).multipart(form);
+            if let Some(ref key) = self.api_key {
+                req = req.header(self.auth_header.as_str(), self.auth_value(key));
+            }
+            Ok(req)
+        })
+        .await?;
+
+        let body: TranscriptionResponse = resp.json().await?;
+        Ok(body.into_transcript(self.name()))
+    }
+
+    fn auth_value(&self, key: &str) -> String {
+        if self.auth_header.eq_ignore_ascii_case("authorization") {
+            format!("Bearer {key}")
+        } else {
+            key.to_string()
+        }
+    }
+}
+
+#[async_trait]
+impl TranscriptionProvider for OpenAiCompatibleProvider {
+    fn name(&self) -> &str {
+        "openai"
+    }
+
+    async fn transcribe(
+        &self,
+        audio: &EncodedAudio,
+        opts: &TranscribeOptions,
+    ) -> Result<Transcript> {
+        self.request(Task::Transcribe, audio, opts).await
+    }
+
+    fn supports_translation(&self) -> bool {
+        true
+    }
+
+    async fn translate(
+        &self,
+        audio: &EncodedAudio,
+        opts: &TranscribeOptions,
+    ) -> Result<Transcript> {
+        self.request(Task::Translate, audio, opts).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::{Matcher, ServerOpts};
+
+    fn dummy_wav() -> EncodedAudio {
+        let mut wav = Vec::new();
+        wav.extend_from_slice(b"RIFF");
+        wav.extend_from_slice(&36u32.to_le_bytes());
+        wav.extend_from_slice(b"WAVE");
+        EncodedAudio::wav(wav)
+    }
+
+    #[tokio::test]
+    async fn transcribe_posts_to_transcriptions_path_with_bearer_auth() {
+        let mut server = mockito::Server::new_with_opts_async(ServerOpts::default()).await;
+        let mock = server
+            .mock("POST", "/v1/audio/transcriptions")
+            .match_header("authorization", "Bearer sk-test")
+            .match_body(Matcher::Regex("whisper-1".into()))
+            .with_status(200)
+            .with_body(r#"{"text":"hello from openai"}"#)
+            .create_async()
+            .await;
+
+        let provider =
+            OpenAiCompatibleProvider::new(server.url() + "/v1/", Some("sk-test".into()), None);
+        let result = provider
+            .transcribe(&dummy_wav(), &TranscribeOptions::default())
+            .await
+            .expect("transcribe should succeed");
+
+        assert_eq!(result.text, "hello from openai");
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn custom_auth_header_carries_bare_key() {
+        let mut server = mockito::Server::new_with_opts_async(ServerOpts::default()).await;
+        let mock = server
+            .mock("POST", "/audio/transcriptions")
+            .match_header("api-key", "secret")
+            .match_header("authorization", Matcher::Missing)
+            .with_status(200)
+            .with_body(r#"{"text":"ok"}"#)
+            .create_async()
+            .await;
+
+        let provider = OpenAiCompatibleProvider::new(
+            server.url(),
+            Some("secret".into()),
+            Some("large-v3".into()),
+        )
+        .with_auth_header("api-key".into());
+        provider
+            .transcribe(&dummy_wav(), &TranscribeOptions::default())
+            .await
+            .expect("transcribe should succeed");
+
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn no_api_key_sends_no_auth_header() {
+        let mut server = mockito::Server::new_with_opts_async(ServerOpts::default()).await;
+        let mock = server
+            .mock("POST", "/audio/transcriptions")
+            .match_header("authorization", Matcher::Missing)
+            .with_status(200)
+            .with_body(r#"{"text":"local"}"#)
+            .create_async()
+            .await;
+
+        let provider = OpenAiCompatibleProvider::new(server.url(), None, None);
+        let result = provider
+            .transcribe(&dummy_wav(), &TranscribeOptions::default())
+            .await
+            .expect("transcribe should succeed");
+
+        assert_eq!(result.text, "local");
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn translate_posts_to_translations_path() {
+        let mut server = mockito::Server::new_with_opts_async(ServerOpts::default()).await;
+        let mock = server
+            .mock("POST", "/v1/audio/translations")
+            .with_status(200)
+            .with_body(r#"{"text":"thank you very much"}"#)
+            .create_async()
+            .await;
+
+        let provider = OpenAiCompatibleProvider::new(server.url() + "/v1", None, None);
+        let result = provider
+            .translate(&dummy_wav(), &TranscribeOptions::default())
+            .await
+            .expect("translate should succeed");
+
+        assert_eq!(result.text, "thank you very much");
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn upload_carries_encoded_file_name_and_mime_type() {
+        let mut server = mockito::Server::new_with_opts_async(ServerOpts::default()).await;
+        let mock = server
+            .mock("POST", "/audio/transcriptions")
+            .match_body(Matcher::AllOf(vec![
+                Matcher::Regex(r#"filename="audio.flac""#.into()),
+                Matcher::Regex("Content-Type: audio/flac".into()),
+            ]))
+            .with_status(200)
+            .with_body(r#"{"text":"compressed"}"#)
+            .create_async()
+            .await;
+
+        let provider = OpenAiCompatibleProvider::new(server.url(), None, None);
+        provider
+            .transcribe(
+                &EncodedAudio::flac(b"fLaC".to_vec()),
+                &TranscribeOptions::default(),
+            )
+            .await
+            .expect("transcribe should succeed");
+
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn http_error_includes_status_hint() {
+        let mut server = mockito::Server::new_with_opts_async(ServerOpts::default()).await;
+        server
+            .mock("POST", "/audio/transcriptions")
+            .with_status(413)
+            .with_body(r#"{"error":{"message":"Maximum content size exceeded"}}"#)
+            .create_async()
+            .await;
+
+        let provider = OpenAiCompatibleProvider::new(server.url(), None, None);
+        let err = provider
+            .transcribe(&dummy_wav(), &TranscribeOptions::default())
+            .await
+            .expect_err("should fail on 413");
+
+        let msg = err.to_string();
+        assert!(msg.contains("413"), "Expected 413 in: {msg}");
+        assert!(msg.contains("too large"), "Expected hint in: {msg}");
+        assert!(
+            msg.contains("Maximum content size"),
+            "Expected API message in: {msg}"
+        );
+    }
+}
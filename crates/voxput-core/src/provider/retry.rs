@@ -0,0 +1,106 @@
+use std::time::{Duration, SystemTime};
+
+/// Retry behaviour for transcription requests.
+///
+/// Retryable failures (see [`VoxputError::is_retryable`]) are retried with
+/// jittered exponential backoff, or after the server's `Retry-After` delay
+/// when a 429/503 response carries one. Retrying stops once `max_attempts`
+/// requests have been made or the next attempt would start after `deadline`.
+///
+/// [`VoxputError::is_retryable`]: crate::errors::VoxputError::is_retryable
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    /// Maximum number of requests, including the first (1 disables retries).
+    pub max_attempts: u32,
+    /// Backoff ceiling before the first retry; doubles for each later retry.
+    pub base_delay: Duration,
+    /// Upper bound on a single backoff delay.
+    pub max_delay: Duration,
+    /// Overall time budget, measured from the first request.
+    pub deadline: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(8),
+            deadline: Duration::from_secs(30),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// A policy that makes a single attempt.
+    pub fn none() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Self::default()
+        }
+    }
+
+    /// Backoff before retry number `retry` (1-based), using "full jitter":
+    /// a uniformly random delay between zero and the exponential ceiling.
+    pub(crate) fn backoff(&self, retry: u32) -> Duration {
+        let factor = 2u32.saturating_pow(retry.saturating_sub(1));
+        let ceiling = self.base_delay.saturating_mul(factor).min(self.max_delay);
+        ceiling.mul_f64(fastrand::f64())
+    }
+}
+
+/// Parse a `Retry-After` header given in delta-seconds or as an HTTP-date
+/// (RFC 9110 §10.2.3). A date already past means retry straight away.
+pub(crate) fn parse_retry_after(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+    let date = httpdate::parse_http_date(value).ok()?;
+    Some(
+        date.duration_since(SystemTime::now())
+            .unwrap_or(Duration::ZERO),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_stays_under_exponential_ceiling() {
+        let policy = RetryPolicy {
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_millis(350),
+            ..RetryPolicy::default()
+        };
+        for _ in 0..100 {
+            assert!(policy.backoff(1) <= Duration::from_millis(100));
+            assert!(policy.backoff(2) <= Duration::from_millis(200));
+            assert!(policy.backoff(3) <= Duration::from_millis(350));
+            assert!(policy.backoff(40) <= Duration::from_millis(350));
+        }
+    }
+
+    #[test]
+    fn retry_after_seconds_parse() {
+        assert_eq!(parse_retry_after("7"), Some(Duration::from_secs(7)));
+        assert_eq!(parse_retry_after(" 0 "), Some(Duration::ZERO));
+        assert_eq!(parse_retry_after("soon"), None);
+    }
+
+    #[test]
+    fn retry_after_date_parses_relative_to_now() {
+        let later = httpdate::fmt_http_date(SystemTime::now() + Duration::from_secs(120));
+        let delay = parse_retry_after(&later).expect("HTTP-date should parse");
+        assert!(
+            (Duration::from_secs(118)..=Duration::from_secs(120)).contains(&delay),
+            "{delay:?}"
+        );
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT"),
+            Some(Duration::ZERO),
+            "a past date means retry now"
+        );
+    }
+}
@@ -0,0 +1,185 @@
+//! End-to-end tests of the record → encode → transcribe → output flow.
+//!
+//! Recordings come from the voice fixture or a generated signal instead of
+//! a sound card, and transcription goes to a local mock server, so these
+//! run anywhere without credentials.
+
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use mockito::{Matcher, ServerOpts};
+use voxput_core::audio::chunk::ChunkOptions;
+use voxput_core::audio::cpal_backend::CpalBackend;
+use voxput_core::audio::decode::decode_file;
+use voxput_core::audio::encode::UploadCodec;
+use voxput_core::audio::segment::SegmentOptions;
+use voxput_core::audio::source::AudioSource;
+use voxput_core::audio::synthetic::{Signal, SyntheticBackend};
+use voxput_core::audio::trim::{trim_silence, TrimOptions};
+use voxput_core::audio::AudioBackend;
+use voxput_core::output::subtitle::CueOptions;
+use voxput_core::output::{format_transcript, OutputFormat};
+use voxput_core::pipeline::{transcribe_audio, transcribe_segments};
+use voxput_core::provider::groq::GroqProvider;
+use voxput_core::provider::TranscribeOptions;
+
+/// Path to the espeak-generated voice fixture shipped with the repo.
+const VOICE_FIXTURE: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/../../tests/fixtures/hello_world.wav"
+);
+
+/// Frames in the fixture (16 kHz mono).
+const FIXTURE_FRAMES: usize = 41481;
+
+/// A backend recording the voice fixture at `speed` times real time.
+fn fixture_backend(speed: f32) -> CpalBackend {
+    let source = AudioSource::File(PathBuf::from(VOICE_FIXTURE));
+    CpalBackend::new(16000).with_source(source.backend(16000, speed))
+}
+
+#[test]
+fn records_the_fixture_without_a_sound_card() {
+    let audio = fixture_backend(0.0)
+        .record(0.0, Arc::new(AtomicBool::new(false)), &[])
+        .unwrap();
+
+    assert_eq!(audio.sample_rate, 16000);
+    assert_eq!(audio.samples.len(), FIXTURE_FRAMES);
+    assert_eq!(
+        audio.samples,
+        decode_file(VOICE_FIXTURE.as_ref()).unwrap().samples
+    );
+}
+
+#[test]
+fn real_time_playback_stops_when_asked() {
+    let stop = Arc::new(AtomicBool::new(false));
+    let setter = Arc::clone(&stop);
+    let started = Instant::now();
+    let stopped = std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(300));
+        setter.store(true, Ordering::SeqCst);
+        started.elapsed()
+    });
+
+    let audio = fixture_backend(1.0).record(0.0, stop, &[]).unwrap();
+    let stopped_after = stopped.join().unwrap();
+
+    // Playback never runs ahead of real time, so no more than was due when
+    // the flag was set (plus generous slack for polling) can be recorded,
+    // however late the flag was set.
+    let secs = audio.duration_secs();
+    let limit = stopped_after.as_secs_f32() + 0.5;
+    assert!(
+        secs <= limit,
+        "{secs} s recorded, stop set after {stopped_after:?}"
+    );
+}
+
+#[test]
+fn duration_limit_applies_to_sources() {
+    let audio = fixture_backend(0.0)
+        .record(1.0, Arc::new(AtomicBool::new(false)), &[])
+        .unwrap();
+    assert_eq!(audio.samples.len(), 16000);
+}
+
+#[tokio::test]
+async fn recording_is_transcribed_and_formatted() {
+    let mut server = mockito::Server::new_with_opts_async(ServerOpts::default()).await;
+    let mock = server
+        .mock("POST", "/audio/transcriptions")
+        .match_header(
+            "content-type",
+            Matcher::Regex("^multipart/form-data".into()),
+        )
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"text":"Hello world, this is a voice dictation test."}"#)
+        .expect(1)
+        .create_async()
+        .await;
+
+    let audio = tokio::task::spawn_blocking(|| {
+        fixture_backend(0.0).record(0.0, Arc::new(AtomicBool::new(false)), &[])
+    })
+    .await
+    .unwrap()
+    .unwrap();
+    let audio = trim_silence(&audio, &TrimOptions::default()).unwrap();
+    assert!(audio.samples.len() <= FIXTURE_FRAMES);
+
+    let provider = GroqProvider::with_api_root("test-key".into(), None, server.url());
+    let transcript = transcribe_audio(
+        &provider,
+        &audio,
+        false,
+        &TranscribeOptions::default(),
+        &ChunkOptions::default(),
+        UploadCodec::Flac,
+    )
+    .await
+    .unwrap();
+    let text = format_transcript(&transcript, OutputFormat::Text, &CueOptions::default()).unwrap();
+
+    assert_eq!(text, "Hello world, this is a voice dictation test.");
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn generated_speech_is_transcribed_incrementally() {
+    let mut server = mockito::Server::new_with_opts_async(ServerOpts::default()).await;
+    let mock = server
+        .mock("POST", "/audio/transcriptions")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"text":"phrase"}"#)
+        .expect(2)
+        .create_async()
+        .await;
+
+    // Two phrases separated by a pause long enough to cut at.
+    let phrases = SyntheticBackend::sequence(
+        16000,
+        vec![
+            (Signal::tone(220.0), 3.5),
+            (Signal::Silence, 1.0),
+            (Signal::tone(330.0), 3.5),
+            (Signal::Silence, 0.3),
+        ],
+    )
+    .with_speed(0.0);
+    let (segment_tx, segment_rx) = tokio::sync::mpsc::unbounded_channel();
+    let recording = tokio::task::spawn_blocking(move || {
+        let backend = CpalBackend::new(16000)
+            .with_source(Some(Arc::new(phrases) as Arc<dyn AudioBackend>))
+            .with_segments(SegmentOptions::default(), move |segment| {
+                let _ = segment_tx.send(segment);
+            });
+        backend.record(0.0, Arc::new(AtomicBool::new(false)), &[])
+    });
+
+    let provider = GroqProvider::with_api_root("test-key".into(), None, server.url());
+    let mut partials = 0;
+    let transcript = transcribe_segments(
+        &provider,
+        segment_rx,
+        false,
+        &TranscribeOptions::default(),
+        &ChunkOptions::default(),
+        UploadCodec::Flac,
+        |_| partials += 1,
+    )
+    .await
+    .unwrap();
+
+    let audio = recording.await.unwrap().unwrap();
+    assert_eq!(audio.samples.len(), (8.3 * 16000.0) as usize);
+    assert_eq!(transcript.text, "phrase phrase");
+    assert_eq!(transcript.provider.as_deref(), Some("groq"));
+    assert_eq!(partials, 2);
+    mock.assert_async().await;
+}
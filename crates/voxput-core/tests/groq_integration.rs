@@ -1,11 +1,12 @@
-/// Integration tests for the Groq Whisper provider.
-///
-/// Requires a valid `GROQ_API_KEY` env var.  Tests are skipped automatically
-/// when the key is absent so CI without credentials stays green.
-///
-/// Run with:
-///   GROQ_API_KEY=gsk_... cargo test --test groq_integration -- --nocapture
+//! Integration tests for the Groq Whisper provider.
+//!
+//! Requires a valid `GROQ_API_KEY` env var.  Tests are skipped automatically
+//! when the key is absent so CI without credentials stays green.
+//!
+//! Run with:
+//!   GROQ_API_KEY=gsk_... cargo test --test groq_integration -- --nocapture
 
+use voxput_core::audio::encode::EncodedAudio;
 use voxput_core::provider::groq::GroqProvider;
 use voxput_core::provider::{TranscribeOptions, TranscriptionProvider};
 
@@ -32,7 +33,7 @@ async fn transcribes_voice_fixture_to_expected_text() {
 
     let provider = GroqProvider::new(key, None);
     let transcript = provider
-        .transcribe(&wav_bytes, &TranscribeOptions::default())
+        .transcribe(&EncodedAudio::wav(wav_bytes), &TranscribeOptions::default())
         .await
         .expect("Groq transcription should succeed");
 
@@ -62,7 +63,7 @@ async fn invalid_api_key_returns_provider_error() {
 
     let provider = GroqProvider::new("bad-key-intentionally-invalid".into(), None);
     let err = provider
-        .transcribe(&wav_bytes, &TranscribeOptions::default())
+        .transcribe(&EncodedAudio::wav(wav_bytes), &TranscribeOptions::default())
         .await
         .expect_err("Should fail with a bad API key");
 
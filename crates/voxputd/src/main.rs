@@ -3,7 +3,9 @@ mod service;
 use miette::IntoDiagnostic;
 use service::VoxputService;
 use tracing_subscriber::EnvFilter;
-use voxput_core::{config::load_config, output::OutputTarget};
+use voxput_core::{
+    config::load_config, errors::VoxputError, output::OutputTarget, provider::create_provider,
+};
 use zbus::connection;
 
 #[tokio::main]
@@ -14,7 +16,13 @@ async fn main() -> miette::Result<()> {
         .init();
 
     let config = load_config()?;
-    let api_key = config.api_key()?;
+    let provider = create_provider(&config)?;
+    if config.translate && !provider.supports_translation() {
+        return Err(VoxputError::TranslationUnsupported {
+            provider: provider.name().to_string(),
+        }
+        .into());
+    }
 
     tracing::info!("Starting voxputd...");
 
@@ -24,7 +32,7 @@ async fn main() -> miette::Result<()> {
         _ => OutputTarget::Clipboard,
     };
 
-    let service = VoxputService::new(api_key, config.model, config.device, None, output_target);
+    let service = VoxputService::new(provider, &config, output_target);
     let inner = service.inner_arc();
 
     let conn = connection::Builder::session()
@@ -1,14 +1,35 @@
+use std::future::Future;
+use std::pin::Pin;
 use std::sync::{
     atomic::{AtomicBool, Ordering},
     Arc, Mutex,
 };
-use tokio::sync::OnceCell;
+use std::task::{Context, Poll};
+use tokio::sync::{mpsc::UnboundedReceiver, OnceCell};
+use tokio_util::sync::CancellationToken;
 use zbus::{interface, object_server::SignalEmitter};
 
 use voxput_core::{
-    audio::{cpal_backend::CpalBackend, wav::encode_wav, AudioBackend, MIN_DURATION_SECS},
+    audio::{
+        chunk::{AudioChunk, ChunkOptions},
+        convert::ChannelMix,
+        cpal_backend::CpalBackend,
+        dsp::{self, DspOptions},
+        encode::UploadCodec,
+        meter::AudioLevel,
+        segment::SegmentOptions,
+        source::AudioSource,
+        trim::{trim_silence, TrimOptions},
+        vad::VadOptions,
+        warm::WarmMic,
+        AudioBackend, MIN_DURATION_SECS,
+    },
+    config::ResolvedConfig,
+    errors::{self, VoxputError},
+    guard::{check_transcript, GuardOptions},
     output::{create_sink, OutputTarget},
-    provider::{groq::GroqProvider, TranscribeOptions, TranscriptionProvider},
+    pipeline::{transcribe_audio, transcribe_segments},
+    provider::{TranscribeOptions, Transcript, TranscriptionProvider},
     state::{DictationEvent, DictationState, DictationStateMachine},
 };
 
@@ -16,18 +37,91 @@ use voxput_core::{
 pub(crate) struct ServiceInner {
     sm: Mutex<DictationStateMachine>,
     stop_flag: Arc<AtomicBool>,
+    /// Cancels the current pipeline run; replaced on each StartRecording.
+    cancel: Mutex<CancellationToken>,
     last_transcript: Mutex<String>,
     last_error: Mutex<String>,
-    api_key: String,
-    model: Option<String>,
-    device_name: Option<String>,
-    language: Option<String>,
+    provider: Box<dyn TranscriptionProvider>,
+    /// Input device patterns in order of preference.
+    devices: Vec<String>,
+    /// Input channel recorded and per-channel gain.
+    channel_mix: ChannelMix,
+    /// Stands in for the input device when `audio.source` is not a device.
+    source: Option<Arc<dyn AudioBackend>>,
+    /// Microphone kept open between recordings; `None` opens it for each one.
+    warm_mic: Option<Arc<WarmMic>>,
+    /// Rate recordings are resampled to before upload.
+    sample_rate: u32,
+    /// Base transcription options; each run attaches its own cancellation token.
+    opts: TranscribeOptions,
+    /// Translate into English instead of transcribing (toggled via SetTranslate).
+    translate: AtomicBool,
+    /// End recordings on trailing silence (toggled via SetAutoStop).
+    auto_stop: AtomicBool,
+    vad: VadOptions,
+    /// Level and filtering applied before upload.
+    dsp: DspOptions,
+    /// Silence trimming applied before upload; `None` when disabled.
+    trim: Option<TrimOptions>,
+    /// Hallucination guard applied to transcripts; `None` when disabled.
+    guard: Option<GuardOptions>,
+    /// Where recordings are cut for incremental transcription; `None`
+    /// transcribes each recording once it has ended.
+    segments: Option<SegmentOptions>,
+    /// How long recordings are split before upload.
+    chunking: ChunkOptions,
+    upload_codec: UploadCodec,
     output_target: OutputTarget,
     /// Stored after D-Bus connection is built; used to emit signals from background tasks.
     pub(crate) connection: OnceCell<zbus::Connection>,
+    /// Partial transcripts emitted so far, for tests to observe without a bus.
+    #[cfg(test)]
+    partials: Mutex<Vec<String>>,
 }
 
 impl ServiceInner {
+    /// Emit a LevelChanged D-Bus signal. No-op if the connection is not yet set.
+    async fn emit_level(&self, level: AudioLevel) {
+        let Some(conn) = self.connection.get() else {
+            return;
+        };
+        match SignalEmitter::new(conn, "/com/github/jonochang/Voxput") {
+            Ok(ctxt) => {
+                let _ =
+                    VoxputService::level_changed(&ctxt, level.rms_db as f64, level.peak_db as f64).await;
+            }
+            Err(e) => tracing::error!("Failed to create signal context: {e}"),
+        }
+    }
+
+    /// Emit a PartialTranscript D-Bus signal. No-op if the connection is not yet set.
+    async fn emit_partial(&self, text: &str) {
+        #[cfg(test)]
+        self.partials.lock().unwrap().push(text.to_string());
+        let Some(conn) = self.connection.get() else {
+            return;
+        };
+        match SignalEmitter::new(conn, "/com/github/jonochang/Voxput") {
+            Ok(ctxt) => {
+                let _ = VoxputService::partial_transcript(&ctxt, text).await;
+            }
+            Err(e) => tracing::error!("Failed to create signal context: {e}"),
+        }
+    }
+
+    /// Emit a NoSpeechDetected D-Bus signal. No-op if the connection is not yet set.
+    async fn emit_no_speech(&self) {
+        let Some(conn) = self.connection.get() else {
+            return;
+        };
+        match SignalEmitter::new(conn, "/com/github/jonochang/Voxput") {
+            Ok(ctxt) => {
+                let _ = VoxputService::no_speech_detected(&ctxt).await;
+            }
+            Err(e) => tracing::error!("Failed to create signal context: {e}"),
+        }
+    }
+
     /// Emit a StateChanged D-Bus signal. No-op if the connection is not yet set.
     async fn emit_state(&self, state: &str, transcript: &str) {
         let Some(conn) = self.connection.get() else {
@@ -48,24 +142,50 @@ pub struct VoxputService {
 
 impl VoxputService {
     pub fn new(
-        api_key: String,
-        model: Option<String>,
-        device_name: Option<String>,
-        language: Option<String>,
+        provider: Box<dyn TranscriptionProvider>,
+        config: &ResolvedConfig,
         output_target: OutputTarget,
     ) -> Self {
         Self {
             inner: Arc::new(ServiceInner {
                 sm: Mutex::new(DictationStateMachine::new()),
                 stop_flag: Arc::new(AtomicBool::new(false)),
+                cancel: Mutex::new(CancellationToken::new()),
                 last_transcript: Mutex::new(String::new()),
                 last_error: Mutex::new(String::new()),
-                api_key,
-                model,
-                device_name,
-                language,
+                provider,
+                devices: config.devices.clone(),
+                channel_mix: config.channel_mix.clone(),
+                source: config.source_backend(),
+                // Files and generated signals have no device to keep open.
+                warm_mic: (config.warm_mic && config.source == AudioSource::Device).then(|| {
+                    Arc::new(WarmMic::new(
+                        Arc::new(
+                            CpalBackend::new(config.sample_rate)
+                                .with_channel_mix(config.channel_mix.clone()),
+                        ),
+                        config.devices.clone(),
+                        config.warm.clone(),
+                    ))
+                }),
+                sample_rate: config.sample_rate,
+                opts: TranscribeOptions {
+                    verbose: config.verbose,
+                    ..Default::default()
+                },
+                translate: AtomicBool::new(config.translate),
+                auto_stop: AtomicBool::new(config.auto_stop),
+                vad: config.vad.clone(),
+                dsp: config.dsp.clone(),
+                trim: config.trim_silence.then(|| config.trim.clone()),
+                guard: config.hallucination_guard.then(|| config.guard.clone()),
+                segments: config.incremental.then(|| config.segments.clone()),
+                chunking: config.chunking.clone(),
+                upload_codec: config.upload_codec,
                 output_target,
                 connection: OnceCell::new(),
+                #[cfg(test)]
+                partials: Mutex::new(Vec::new()),
             }),
         }
     }
@@ -99,6 +219,7 @@ impl VoxputService {
             sm.handle(DictationEvent::StartRecording);
         }
         self.inner.stop_flag.store(false, Ordering::SeqCst);
+        *self.inner.cancel.lock().unwrap() = CancellationToken::new();
         self.inner.emit_state("recording", "").await;
         self.spawn_pipeline();
         Ok(())
@@ -122,6 +243,37 @@ impl VoxputService {
         }
     }
 
+    /// Abandon the current recording or in-flight transcription and return to idle.
+    /// No-op when idle.
+    async fn cancel(&self) -> zbus::fdo::Result<()> {
+        let state = self.inner.sm.lock().unwrap().state();
+        if matches!(state, DictationState::Recording | DictationState::Transcribing) {
+            self.inner.cancel.lock().unwrap().cancel();
+            self.inner.stop_flag.store(true, Ordering::SeqCst);
+        }
+        Ok(())
+    }
+
+    /// Enable or disable translate-to-English mode for subsequent recordings.
+    async fn set_translate(&self, enabled: bool) -> zbus::fdo::Result<()> {
+        if enabled && !self.inner.provider.supports_translation() {
+            return Err(zbus::fdo::Error::NotSupported(format!(
+                "provider '{}' does not support translation",
+                self.inner.provider.name()
+            )));
+        }
+        self.inner.translate.store(enabled, Ordering::SeqCst);
+        tracing::info!(enabled, "Translate mode changed");
+        Ok(())
+    }
+
+    /// Enable or disable stopping on trailing silence for subsequent recordings.
+    async fn set_auto_stop(&self, enabled: bool) -> zbus::fdo::Result<()> {
+        self.inner.auto_stop.store(enabled, Ordering::SeqCst);
+        tracing::info!(enabled, "Auto-stop mode changed");
+        Ok(())
+    }
+
     /// Return (state, last_transcript, last_error) strings.
     async fn get_status(&self) -> zbus::fdo::Result<(String, String, String)> {
         let state = self.inner.sm.lock().unwrap().state().to_string();
@@ -130,6 +282,22 @@ impl VoxputService {
         Ok((state, transcript, error))
     }
 
+    /// Emitted about 20 times a second while recording with the input level
+    /// in dBFS: `rms` (full-scale sine ≈ -3) and `peak`, both floored at -100.
+    #[zbus(signal)]
+    async fn level_changed(ctxt: &SignalEmitter<'_>, rms: f64, peak: f64) -> zbus::Result<()>;
+
+    /// Emitted in incremental mode each time another segment of the current
+    /// recording has been transcribed. `text` is the transcript so far; the
+    /// complete text follows in StateChanged when the state returns to "idle".
+    #[zbus(signal)]
+    async fn partial_transcript(ctxt: &SignalEmitter<'_>, text: &str) -> zbus::Result<()>;
+
+    /// Emitted when a recording is discarded because it held no speech, or
+    /// its transcript looked like a hallucination; the state returns to "idle".
+    #[zbus(signal)]
+    async fn no_speech_detected(ctxt: &SignalEmitter<'_>) -> zbus::Result<()>;
+
     /// Emitted whenever the daemon's state changes.
     /// `state` is one of: "idle", "recording", "transcribing", "error".
     /// `transcript` is the completed text (only set when state returns to "idle").
@@ -149,11 +317,65 @@ async fn run_pipeline(inner: Arc<ServiceInner>) {
     tracing::info!("Pipeline: recording");
 
     let stop_flag = Arc::clone(&inner.stop_flag);
-    let device = inner.device_name.clone();
+    let devices = inner.devices.clone();
+    let sample_rate = inner.sample_rate;
+    let channel_mix = inner.channel_mix.clone();
+    let source = inner.source.clone();
+    let warm_mic = inner.warm_mic.clone();
+    let cancel = inner.cancel.lock().unwrap().clone();
+    let vad = inner
+        .auto_stop
+        .load(Ordering::SeqCst)
+        .then(|| inner.vad.clone());
+    let opts = TranscribeOptions {
+        cancel: cancel.clone(),
+        ..inner.opts.clone()
+    };
+    let translate = inner.translate.load(Ordering::SeqCst);
+
+    // Levels arrive on the recording thread; forward them as signals from here.
+    let (level_tx, mut level_rx) = tokio::sync::mpsc::unbounded_channel();
+    let meter_inner = Arc::clone(&inner);
+    tokio::spawn(async move {
+        while let Some(level) = level_rx.recv().await {
+            meter_inner.emit_level(level).await;
+        }
+    });
+
+    // In incremental mode, segments cut at pauses are filtered, normalized
+    // and transcribed in the background while recording goes on. Returning
+    // early drops the task, which stops its uploads and partial transcripts.
+    let (segment_tx, live) = match &inner.segments {
+        Some(segments) => {
+            let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+            let task = AbortOnDrop(tokio::spawn(transcribe_live(
+                Arc::clone(&inner),
+                rx,
+                translate,
+                opts.clone(),
+            )));
+            (Some((segments.clone(), inner.dsp.clone(), tx)), Some(task))
+        }
+        None => (None, None),
+    };
 
-    // 1. Record (blocking)
+    // 1. Record (blocking); auto-stop ends it on silence like StopRecording would
     let audio = match tokio::task::spawn_blocking(move || {
-        CpalBackend.record(0.0, stop_flag, device.as_deref())
+        let mut backend = CpalBackend::new(sample_rate)
+            .with_source(source)
+            .with_channel_mix(channel_mix)
+            .with_warm_mic(warm_mic)
+            .with_auto_stop(vad)
+            .with_level_callback(move |level| {
+                let _ = level_tx.send(level);
+            });
+        if let Some((segments, dsp_opts, segment_tx)) = segment_tx {
+            backend = backend.with_segments(segments, move |mut segment| {
+                dsp::process(&mut segment.audio, &dsp_opts);
+                let _ = segment_tx.send(segment);
+            });
+        }
+        backend.record(0.0, stop_flag, &devices)
     })
     .await
     {
@@ -168,6 +390,11 @@ async fn run_pipeline(inner: Arc<ServiceInner>) {
         }
     };
 
+    if cancel.is_cancelled() {
+        pipeline_cancelled(&inner).await;
+        return;
+    }
+
     // 2. Duration guard
     if audio.duration_secs() < MIN_DURATION_SECS {
         pipeline_error(
@@ -181,14 +408,26 @@ async fn run_pipeline(inner: Arc<ServiceInner>) {
         return;
     }
 
-    // 3. Encode WAV in memory
-    let wav = match encode_wav(&audio) {
-        Ok(w) => w,
-        Err(e) => {
-            pipeline_error(&inner, &e.to_string()).await;
-            return;
-        }
+    // 3. Trim the key click and trailing silence (silent recordings are not
+    //    uploaded), then filter and normalize what is left; incremental
+    //    segments were cut around speech and processed as they were recorded
+    let mut audio = match &inner.trim {
+        Some(trim) if live.is_none() => match trim_silence(&audio, trim) {
+            Ok(trimmed) => trimmed,
+            Err(VoxputError::NoSpeech) => {
+                pipeline_no_speech(&inner).await;
+                return;
+            }
+            Err(e) => {
+                pipeline_error(&inner, &e.to_string()).await;
+                return;
+            }
+        },
+        _ => audio,
     };
+    if live.is_none() {
+        dsp::process(&mut audio, &inner.dsp);
+    }
 
     // 4. Advance state machine: Recording → Transcribing
     {
@@ -196,15 +435,43 @@ async fn run_pipeline(inner: Arc<ServiceInner>) {
         sm.handle(DictationEvent::StopRecording);
     }
     inner.emit_state("transcribing", "").await;
-    tracing::info!("Pipeline: transcribing");
-
-    // 5. Transcribe
-    let provider = GroqProvider::new(inner.api_key.clone(), inner.model.clone());
-    let mut opts = TranscribeOptions::default();
-    opts.language = inner.language.clone();
+    tracing::info!(provider = inner.provider.name(), "Pipeline: transcribing");
 
-    let transcript_text = match provider.transcribe(&wav, &opts).await {
-        Ok(t) => t.text,
+    // 5. Transcribe (long recordings are split into chunks); in incremental
+    //    mode only the segments still in flight are left to wait for
+    let transcribed = match live {
+        Some(task) => task
+            .await
+            .unwrap_or_else(|e| Err(VoxputError::Audio(format!("Transcription task failed: {e}")))),
+        None => {
+            transcribe_audio(
+                &*inner.provider,
+                &audio,
+                translate,
+                &opts,
+                &inner.chunking,
+                inner.upload_codec,
+            )
+            .await
+        }
+    };
+    let result = transcribed.and_then(|t| match &inner.guard {
+        Some(guard) => check_transcript(&audio, t, guard),
+        None => Ok(t),
+    });
+    let transcript_text = match result {
+        Ok(t) => {
+            tracing::debug!(provider = ?t.provider, "Pipeline: transcribed");
+            t.text
+        }
+        Err(VoxputError::Cancelled) => {
+            pipeline_cancelled(&inner).await;
+            return;
+        }
+        Err(VoxputError::NoSpeech) => {
+            pipeline_no_speech(&inner).await;
+            return;
+        }
         Err(e) => {
             {
                 let mut sm = inner.sm.lock().unwrap();
@@ -238,6 +505,58 @@ async fn run_pipeline(inner: Arc<ServiceInner>) {
     .ok();
 }
 
+/// Transcribe incremental segments as they arrive, emitting PartialTranscript
+/// signals along the way.
+async fn transcribe_live(
+    inner: Arc<ServiceInner>,
+    segments: UnboundedReceiver<AudioChunk>,
+    translate: bool,
+    opts: TranscribeOptions,
+) -> errors::Result<Transcript> {
+    let (partial_tx, mut partial_rx) = tokio::sync::mpsc::unbounded_channel::<String>();
+    let emitter = Arc::clone(&inner);
+    // Aborted with this task, so no partial follows a cancelled recording.
+    let emitting = AbortOnDrop(tokio::spawn(async move {
+        while let Some(text) = partial_rx.recv().await {
+            emitter.emit_partial(&text).await;
+        }
+    }));
+    let transcript = transcribe_segments(
+        &*inner.provider,
+        segments,
+        translate,
+        &opts,
+        &inner.chunking,
+        inner.upload_codec,
+        |t| {
+            let _ = partial_tx.send(t.text.clone());
+        },
+    )
+    .await;
+    // Let the last partials out before the final transcript.
+    drop(partial_tx);
+    let _ = emitting.await;
+    transcript
+}
+
+/// A spawned task that is aborted when its handle is dropped, so a pipeline
+/// run that ends early does not leave it running.
+struct AbortOnDrop<T>(tokio::task::JoinHandle<T>);
+
+impl<T> Drop for AbortOnDrop<T> {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+impl<T> Future for AbortOnDrop<T> {
+    type Output = Result<T, tokio::task::JoinError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        Pin::new(&mut self.0).poll(cx)
+    }
+}
+
 async fn pipeline_error(inner: &Arc<ServiceInner>, error: &str) {
     tracing::error!("Pipeline error: {error}");
     {
@@ -258,3 +577,90 @@ async fn pipeline_error(inner: &Arc<ServiceInner>, error: &str) {
     *inner.last_error.lock().unwrap() = error.to_string();
     inner.emit_state("error", "").await;
 }
+
+async fn pipeline_cancelled(inner: &Arc<ServiceInner>) {
+    tracing::info!("Pipeline: cancelled");
+    inner.sm.lock().unwrap().handle(DictationEvent::Reset);
+    inner.emit_state("idle", "").await;
+}
+
+async fn pipeline_no_speech(inner: &Arc<ServiceInner>) {
+    tracing::info!("Pipeline: no speech detected");
+    inner.sm.lock().unwrap().handle(DictationEvent::Reset);
+    inner.emit_state("idle", "").await;
+    inner.emit_no_speech().await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use std::time::Duration;
+    use voxput_core::audio::encode::EncodedAudio;
+    use voxput_core::audio::source::AudioSource;
+
+    /// Takes a while over every segment, ignoring cancellation like a slow
+    /// upload would.
+    struct SlowProvider;
+
+    #[async_trait]
+    impl TranscriptionProvider for SlowProvider {
+        fn name(&self) -> &str {
+            "slow"
+        }
+
+        async fn transcribe(&self, _: &EncodedAudio, _: &TranscribeOptions) -> errors::Result<Transcript> {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            Ok(Transcript {
+                text: "phrase".into(),
+                ..Default::default()
+            })
+        }
+    }
+
+    /// A daemon recording an endless tone, cut into one-second segments.
+    fn incremental_service() -> VoxputService {
+        let mut config = ResolvedConfig {
+            source: AudioSource::Tone(220.0),
+            source_speed: 10.0,
+            incremental: true,
+            hallucination_guard: false,
+            ..ResolvedConfig::default()
+        };
+        config.segments.max_segment_secs = 1.0;
+        VoxputService::new(Box::new(SlowProvider), &config, OutputTarget::Stdout)
+    }
+
+    #[tokio::test]
+    async fn no_partial_transcript_follows_a_cancel() {
+        let service = incremental_service();
+        let inner = service.inner_arc();
+        service.start_recording().await.unwrap();
+
+        let started = tokio::time::Instant::now();
+        while inner.partials.lock().unwrap().is_empty() {
+            assert!(started.elapsed() < Duration::from_secs(10), "no partial transcript arrived");
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        service.cancel().await.unwrap();
+        while inner.sm.lock().unwrap().state() != DictationState::Idle {
+            assert!(started.elapsed() < Duration::from_secs(10), "cancel did not return to idle");
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+
+        let emitted = inner.partials.lock().unwrap().len();
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        assert_eq!(inner.partials.lock().unwrap().len(), emitted, "partials arrived after the cancel");
+    }
+
+    #[test]
+    fn warm_mic_is_not_used_for_a_generated_source() {
+        let config = ResolvedConfig {
+            source: AudioSource::Tone(220.0),
+            warm_mic: true,
+            ..ResolvedConfig::default()
+        };
+        let service = VoxputService::new(Box::new(SlowProvider), &config, OutputTarget::Stdout);
+        assert!(service.inner.warm_mic.is_none());
+    }
+}
@@ -33,6 +33,7 @@ const DBUS_IFACE = `
       <arg type="s" name="state"/>
       <arg type="s" name="transcript"/>
     </signal>
+    <signal name="NoSpeechDetected"/>
   </interface>
 </node>`;
 
@@ -68,6 +69,7 @@ export default class VoxputExtension extends Extension {
         this._lastTranscript = '';
         this._proxy = null;
         this._signalId = null;
+        this._noSpeechSignalId = null;
         this._nameWatchId = null;
         this._keyReleaseId = null;
         this._grabActor = null;
@@ -182,6 +184,10 @@ export default class VoxputExtension extends Extension {
                     this._onStateChanged(state, transcript);
                 },
             );
+            this._noSpeechSignalId = this._proxy.connectSignal(
+                'NoSpeechDetected',
+                () => Main.notify(_('Voxput'), _('No speech detected.')),
+            );
 
             this._refreshStatus();
 
@@ -210,6 +216,10 @@ export default class VoxputExtension extends Extension {
             this._proxy.disconnectSignal(this._signalId);
             this._signalId = null;
         }
+        if (this._proxy && this._noSpeechSignalId) {
+            this._proxy.disconnectSignal(this._noSpeechSignalId);
+            this._noSpeechSignalId = null;
+        }
         this._proxy = null;
     }
 